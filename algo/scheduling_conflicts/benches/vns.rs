@@ -0,0 +1,51 @@
+//! Measures the variable neighborhood search on the largest test instance
+//! with the incremental evaluation of moves against rescoring a full clone for every move.
+//! Run with `cargo bench --bench vns`.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::time::{Duration, Instant};
+
+use scheduling_conflicts::{serialization, Instance, Schedule, Scheduler, Vns};
+
+const INSTANCE: &str = "src/test/test100.txt";
+const SEEDS: u64 = 20;
+
+/// Runs the search with every seed. Returns the mean time and the mean score.
+fn measure<'a>(run: impl Fn(Vns) -> Schedule<'a>) -> (Duration, f64) {
+    let mut total = Duration::ZERO;
+    let mut score = 0;
+
+    for seed in 0..SEEDS {
+        let vns = Vns {
+            seed: Some(seed),
+            shakes: 50,
+            ..Vns::default()
+        };
+
+        let start = Instant::now();
+        let schedule = run(vns);
+        total += start.elapsed();
+        score += schedule.calculate_score();
+    }
+
+    (total / SEEDS as u32, score as f64 / SEEDS as f64)
+}
+
+fn main() {
+    let mut reader = BufReader::new(File::open(INSTANCE).expect("Error opening instance"));
+    let instance: Instance =
+        serialization::deserialize(&mut reader).expect("Error reading instance");
+
+    let (incremental_time, incremental_score) = measure(|vns| vns.schedule(&instance));
+    let (full_time, full_score) = measure(|vns| vns.schedule_full_rescore(&instance));
+
+    println!("instance: {INSTANCE}");
+    println!("runs: {SEEDS}");
+    println!("incremental: mean time {incremental_time:.2?}, mean score {incremental_score:.2}");
+    println!("full rescore: mean time {full_time:.2?}, mean score {full_score:.2}");
+    println!(
+        "speed-up: {:.2}x",
+        full_time.as_secs_f64() / incremental_time.as_secs_f64()
+    );
+}
@@ -0,0 +1,350 @@
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use scheduling_conflicts::bounds::{self, Evaluation};
+use scheduling_conflicts::{
+    list_algorithm, serialization, BranchAndBound, Instance, Portfolio, Schedule, Scheduler,
+    SimulatedAnnealing, TabuSearch, Tresoldi, Vns,
+};
+use serde::Serialize;
+
+const USAGE: &str = "Usage: bench DIRECTORY [--schedulers NAME,...] [--repetitions N] [--seed N] \
+[--time-limit MS] [--format csv|json] [--runs]
+Schedulers: list, vns, tresoldi, branch_and_bound, portfolio, tabu_search, simulated_annealing
+Repetition r of seeded schedulers uses the seed + r. The time limit applies to every scheduler
+but list, which always runs to completion, and to every search of the portfolio.
+Without --runs the runs of every instance and scheduler are summarised in a single row.
+The upper bound and gap are left empty for minimised objectives.";
+
+/// A scheduler compared by the benchmark.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Algorithm {
+    List,
+    Vns,
+    Tresoldi,
+    BranchAndBound,
+    Portfolio,
+    TabuSearch,
+    SimulatedAnnealing,
+}
+
+impl Algorithm {
+    fn name(self) -> &'static str {
+        match self {
+            Algorithm::List => "list",
+            Algorithm::Vns => "vns",
+            Algorithm::Tresoldi => "tresoldi",
+            Algorithm::BranchAndBound => "branch_and_bound",
+            Algorithm::Portfolio => "portfolio",
+            Algorithm::TabuSearch => "tabu_search",
+            Algorithm::SimulatedAnnealing => "simulated_annealing",
+        }
+    }
+
+    /// Runs the scheduler with the seed and the time limit in milliseconds if it takes them.
+    /// The list algorithm takes neither.
+    fn run(self, instance: &Instance, seed: u64, time_limit: Option<u64>) -> Schedule<'_> {
+        let seed = Some(seed);
+        match self {
+            Algorithm::List => list_algorithm(instance),
+            Algorithm::Vns => Vns {
+                seed,
+                time_limit,
+                ..Vns::default()
+            }
+            .schedule(instance),
+            Algorithm::Tresoldi => Tresoldi {
+                seed,
+                time_limit,
+                ..Tresoldi::default()
+            }
+            .schedule(instance),
+            Algorithm::BranchAndBound => match time_limit {
+                Some(time_limit) => BranchAndBound::new(Duration::from_millis(time_limit)),
+                None => BranchAndBound::default(),
+            }
+            .schedule(instance),
+            Algorithm::Portfolio => Portfolio {
+                seed,
+                ..Portfolio::default()
+            }
+            .with_time_limit(time_limit)
+            .schedule(instance),
+            Algorithm::TabuSearch => TabuSearch {
+                time_limit,
+                ..TabuSearch::default()
+            }
+            .schedule(instance),
+            Algorithm::SimulatedAnnealing => SimulatedAnnealing {
+                seed,
+                time_limit,
+                ..SimulatedAnnealing::default()
+            }
+            .schedule(instance),
+        }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        [
+            Algorithm::List,
+            Algorithm::Vns,
+            Algorithm::Tresoldi,
+            Algorithm::BranchAndBound,
+            Algorithm::Portfolio,
+            Algorithm::TabuSearch,
+            Algorithm::SimulatedAnnealing,
+        ]
+        .into_iter()
+        .find(|algorithm| algorithm.name() == name)
+        .ok_or_else(|| format!("unknown scheduler {name}"))
+    }
+}
+
+/// Parameters of the benchmark.
+struct Config {
+    directory: PathBuf,
+    schedulers: Vec<Algorithm>,
+    repetitions: usize,
+    seed: u64,
+    time_limit: Option<u64>,
+    json: bool,
+    runs: bool,
+}
+
+/// Reads the parameters from the command line arguments.
+fn parse_args() -> Result<Config, Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let mut config = Config {
+        directory: args.next().ok_or("missing directory")?.into(),
+        schedulers: vec![Algorithm::List, Algorithm::Vns, Algorithm::Tresoldi],
+        repetitions: 1,
+        seed: 0,
+        time_limit: None,
+        json: false,
+        runs: false,
+    };
+
+    while let Some(flag) = args.next() {
+        if flag == "--runs" {
+            config.runs = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value of {flag}"))?;
+        match flag.as_str() {
+            "--schedulers" => {
+                config.schedulers = value.split(',').map(str::parse).collect::<Result<_, _>>()?
+            }
+            "--repetitions" => config.repetitions = value.parse()?,
+            "--seed" => config.seed = value.parse()?,
+            "--time-limit" => config.time_limit = Some(value.parse()?),
+            "--format" => match value.as_str() {
+                "csv" => config.json = false,
+                "json" => config.json = true,
+                _ => return Err(format!("unknown format {value}").into()),
+            },
+            _ => return Err(format!("unknown argument {flag}").into()),
+        }
+    }
+
+    Ok(config)
+}
+
+/// A single run of a scheduler on an instance.
+#[derive(Clone, Debug, Serialize)]
+struct Run {
+    instance: String,
+    scheduler: &'static str,
+    repetition: usize,
+    seed: u64,
+    score: u64,
+    objective_value: u64,
+    upper_bound: Option<u64>,
+    gap: Option<f64>,
+    runtime_ms: f64,
+    valid: bool,
+}
+
+/// Runs of a scheduler on an instance summarised.
+#[derive(Clone, Debug, Serialize)]
+struct Summary {
+    instance: String,
+    scheduler: &'static str,
+    runs: usize,
+    mean_score: f64,
+    best_score: u64,
+    mean_objective_value: f64,
+    mean_gap: Option<f64>,
+    mean_runtime_ms: f64,
+    invalid: usize,
+}
+
+impl Summary {
+    fn new(runs: &[Run]) -> Self {
+        let mean =
+            |value: fn(&Run) -> f64| runs.iter().map(value).sum::<f64>() / runs.len().max(1) as f64;
+
+        Self {
+            instance: runs[0].instance.clone(),
+            scheduler: runs[0].scheduler,
+            runs: runs.len(),
+            mean_score: mean(|run| run.score as f64),
+            best_score: runs.iter().map(|run| run.score).max().unwrap_or_default(),
+            mean_objective_value: mean(|run| run.objective_value as f64),
+            mean_gap: runs
+                .iter()
+                .map(|run| run.gap)
+                .sum::<Option<f64>>()
+                .map(|gap| gap / runs.len().max(1) as f64),
+            mean_runtime_ms: mean(|run| run.runtime_ms),
+            invalid: runs.iter().filter(|run| !run.valid).count(),
+        }
+    }
+}
+
+/// A row of the CSV table.
+trait CsvRow {
+    const HEADER: &'static str;
+
+    fn fields(&self) -> Vec<String>;
+}
+
+impl CsvRow for Run {
+    const HEADER: &'static str =
+        "instance,scheduler,repetition,seed,score,objective_value,upper_bound,gap,runtime_ms,valid";
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            csv_text(&self.instance),
+            self.scheduler.to_string(),
+            self.repetition.to_string(),
+            self.seed.to_string(),
+            self.score.to_string(),
+            self.objective_value.to_string(),
+            self.upper_bound
+                .map_or_else(String::new, |upper_bound| upper_bound.to_string()),
+            self.gap.map_or_else(String::new, |gap| format!("{gap:.4}")),
+            format!("{:.3}", self.runtime_ms),
+            self.valid.to_string(),
+        ]
+    }
+}
+
+impl CsvRow for Summary {
+    const HEADER: &'static str = "instance,scheduler,runs,mean_score,best_score,\
+mean_objective_value,mean_gap,mean_runtime_ms,invalid";
+
+    fn fields(&self) -> Vec<String> {
+        vec![
+            csv_text(&self.instance),
+            self.scheduler.to_string(),
+            self.runs.to_string(),
+            format!("{:.2}", self.mean_score),
+            self.best_score.to_string(),
+            format!("{:.2}", self.mean_objective_value),
+            self.mean_gap
+                .map_or_else(String::new, |gap| format!("{gap:.4}")),
+            format!("{:.3}", self.mean_runtime_ms),
+            self.invalid.to_string(),
+        ]
+    }
+}
+
+/// Quotes the text if it contains characters special in CSV.
+fn csv_text(text: &str) -> String {
+    if text.contains([',', '"', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Writes the rows to stdout as CSV or JSON.
+fn write_table<T: CsvRow + Serialize>(rows: &[T], json: bool) -> Result<(), Box<dyn Error>> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(rows)?);
+    } else {
+        println!("{}", T::HEADER);
+        for row in rows {
+            println!("{}", row.fields().join(","));
+        }
+    }
+    Ok(())
+}
+
+/// Reads and validates the instance from the file.
+fn read_instance(path: &Path) -> Result<Instance, Box<dyn Error>> {
+    let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+    let instance: Instance = serialization::deserialize(&mut reader)?;
+    instance.validate()?;
+    Ok(instance)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let config = parse_args().unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        std::process::exit(1);
+    });
+
+    let mut paths = std::fs::read_dir(&config.directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    paths.retain(|path| path.is_file());
+    paths.sort();
+
+    let mut runs = Vec::new();
+    let mut summaries = Vec::new();
+
+    for path in paths {
+        let instance =
+            read_instance(&path).map_err(|error| format!("{}: {error}", path.display()))?;
+        let name = path
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let upper_bound = bounds::upper_bound(&instance);
+
+        for &scheduler in &config.schedulers {
+            let mut scheduler_runs = Vec::with_capacity(config.repetitions);
+
+            for repetition in 0..config.repetitions {
+                let seed = config.seed.wrapping_add(repetition as u64);
+                let start = Instant::now();
+                let schedule = scheduler.run(&instance, seed, config.time_limit);
+                let runtime = start.elapsed();
+                let evaluation = Evaluation::with_bound(&schedule, upper_bound);
+
+                scheduler_runs.push(Run {
+                    instance: name.clone(),
+                    scheduler: scheduler.name(),
+                    repetition,
+                    seed,
+                    score: evaluation.score,
+                    objective_value: evaluation.objective_value,
+                    upper_bound: evaluation.upper_bound,
+                    gap: evaluation.gap,
+                    runtime_ms: runtime.as_secs_f64() * 1000.0,
+                    valid: schedule.verify(),
+                });
+            }
+
+            if !scheduler_runs.is_empty() {
+                summaries.push(Summary::new(&scheduler_runs));
+            }
+            runs.extend(scheduler_runs);
+        }
+    }
+
+    if config.runs {
+        write_table(&runs, config.json)
+    } else {
+        write_table(&summaries, config.json)
+    }
+}
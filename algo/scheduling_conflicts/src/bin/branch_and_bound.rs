@@ -0,0 +1,5 @@
+use scheduling_conflicts::{branch_and_bound, run_experiment_from_stdin};
+
+fn main() {
+    run_experiment_from_stdin(branch_and_bound).expect("Failed to run branch and bound algorithm");
+}
@@ -0,0 +1,42 @@
+use std::error::Error;
+
+use scheduling_conflicts::generator::Generator;
+use scheduling_conflicts::serialization;
+
+const USAGE: &str = "Usage: generate [--tasks N] [--processors N] [--tightness F] \
+[--processing-time DISTRIBUTION] [--weight DISTRIBUTION] [--conflicts MODEL] [--seed N]
+Distributions: constant:VALUE, uniform:MIN:MAX, exponential:MEAN
+Conflict models: er:DENSITY, interval:LENGTH, cliques:CLUSTERS:DENSITY";
+
+/// Reads the generator parameters from the command line arguments.
+fn parse_args() -> Result<Generator, Box<dyn Error>> {
+    let mut generator = Generator::default();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value of {flag}"))?;
+        match flag.as_str() {
+            "--tasks" => generator.tasks = value.parse()?,
+            "--processors" => generator.processors = value.parse()?,
+            "--tightness" => generator.tightness = value.parse()?,
+            "--processing-time" => generator.processing_time = value.parse()?,
+            "--weight" => generator.weight = value.parse()?,
+            "--conflicts" => generator.conflicts = value.parse()?,
+            "--seed" => generator.seed = Some(value.parse()?),
+            _ => return Err(format!("unknown argument {flag}").into()),
+        }
+    }
+
+    Ok(generator)
+}
+
+fn main() {
+    let generator = parse_args().unwrap_or_else(|error| {
+        eprintln!("{error}\n{USAGE}");
+        std::process::exit(1);
+    });
+
+    serialization::to_stdout(&generator.generate()).expect("Failed to write the instance");
+}
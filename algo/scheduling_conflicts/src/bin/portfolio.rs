@@ -0,0 +1,5 @@
+use scheduling_conflicts::{portfolio, run_experiment_from_stdin};
+
+fn main() {
+    run_experiment_from_stdin(portfolio).expect("Failed to run portfolio algorithm");
+}
@@ -0,0 +1,6 @@
+use scheduling_conflicts::{run_experiment_from_stdin, simulated_annealing};
+
+fn main() {
+    run_experiment_from_stdin(simulated_annealing)
+        .expect("Failed to run simulated annealing algorithm");
+}
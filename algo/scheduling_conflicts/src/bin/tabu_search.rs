@@ -0,0 +1,5 @@
+use scheduling_conflicts::{run_experiment_from_stdin, tabu_search};
+
+fn main() {
+    run_experiment_from_stdin(tabu_search).expect("Failed to run tabu search algorithm");
+}
@@ -0,0 +1,225 @@
+use ahash::{HashSet, HashSetExt};
+use serde::{Deserialize, Serialize};
+
+use crate::{Instance, Schedule};
+
+/// Score of a schedule compared with the upper bound of its instance.
+/// Also contains the value of the objective of the instance, see [`crate::Objective::value`].
+/// The bounds are on the weight of tasks on time, so minimised objectives
+/// have neither an upper bound nor a gap.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Evaluation {
+    pub score: u64,
+    pub upper_bound: Option<u64>,
+    pub gap: Option<f64>,
+    pub objective_value: u64,
+}
+
+impl Evaluation {
+    /// Evaluates the schedule using the best relaxation bound of its instance.
+    pub fn new(schedule: &Schedule) -> Self {
+        Self::with_bound(schedule, upper_bound(schedule.instance()))
+    }
+
+    /// Evaluates the schedule using the given upper bound.
+    /// The bound is left out for minimised objectives.
+    pub fn with_bound(schedule: &Schedule, upper_bound: u64) -> Self {
+        let score = schedule.calculate_score();
+        let objective = schedule.instance().objective;
+        let upper_bound = Some(upper_bound).filter(|_| !objective.is_minimised());
+        Self {
+            score,
+            upper_bound,
+            gap: upper_bound.map(|upper_bound| optimality_gap(score, upper_bound)),
+            objective_value: objective.value(schedule),
+        }
+    }
+}
+
+/// Returns the relative gap between the score and the upper bound.
+/// It's zero when the score reaches the bound.
+pub fn optimality_gap(score: u64, upper_bound: u64) -> f64 {
+    if upper_bound == 0 {
+        0.0
+    } else {
+        upper_bound.saturating_sub(score) as f64 / upper_bound as f64
+    }
+}
+
+/// Returns the tightest of the available upper bounds.
+pub fn upper_bound(instance: &Instance) -> u64 {
+    knapsack_bound(instance).min(clique_bound(instance))
+}
+
+/// Fractional knapsack bound over the total machine capacity `processors * deadline`
+/// reduced by blocked windows of machines.
+/// Ignores conflicts between tasks.
+pub fn knapsack_bound(instance: &Instance) -> u64 {
+    let capacity = (0..instance.processors)
+        .map(|machine| instance.machine_capacity(machine))
+        .sum();
+    fractional_knapsack(instance, (0..instance.tasks.len()).collect(), capacity)
+}
+
+/// Bound from a partition of the conflict graph into cliques.
+/// Tasks from the same clique can't overlap, so every clique is a knapsack with capacity `deadline`.
+/// Ignores the number of processors.
+pub fn clique_bound(instance: &Instance) -> u64 {
+    clique_partition(instance)
+        .into_iter()
+        .map(|clique| fractional_knapsack(instance, clique, instance.deadline))
+        .sum()
+}
+
+/// Greedily partitions the tasks into cliques of the conflicts including shared resources.
+/// Tasks with the most conflicts are used first to start new cliques.
+pub fn clique_partition(instance: &Instance) -> Vec<Vec<usize>> {
+    let degrees: Vec<usize> = (0..instance.tasks.len())
+        .map(|task| instance.conflicts(task).count())
+        .collect();
+    let mut tasks: Vec<usize> = (0..instance.tasks.len()).collect();
+    tasks.sort_unstable_by_key(|&task| std::cmp::Reverse(degrees[task]));
+
+    let mut assigned = HashSet::with_capacity(tasks.len());
+    let mut cliques = Vec::new();
+
+    for &task in &tasks {
+        if !assigned.insert(task) {
+            continue;
+        }
+
+        let mut clique = vec![task];
+        let mut candidates: Vec<usize> = instance
+            .conflicts(task)
+            .filter(|other| !assigned.contains(other))
+            .collect();
+        candidates.sort_unstable_by_key(|&other| (std::cmp::Reverse(degrees[other]), other));
+        candidates.dedup();
+
+        for candidate in candidates {
+            if clique
+                .iter()
+                .all(|&member| instance.are_conflicted(member, candidate))
+            {
+                assigned.insert(candidate);
+                clique.push(candidate);
+            }
+        }
+
+        cliques.push(clique);
+    }
+
+    cliques
+}
+
+/// Fractional knapsack over the given tasks using their shortest processing times.
+fn fractional_knapsack(instance: &Instance, tasks: Vec<usize>, mut capacity: u64) -> u64 {
+    let mut items: Vec<(u64, u64)> = tasks
+        .into_iter()
+        .filter_map(|task| {
+            instance
+                .min_processing_time(task)
+                .filter(|&time| instance.release_time(task) + time <= instance.due_date(task))
+                .map(|time| (time, instance.tasks[task].weight))
+        })
+        .collect();
+    items.sort_unstable_by(|a, b| (a.0 as u128 * b.1 as u128).cmp(&(b.0 as u128 * a.1 as u128)));
+
+    let mut bound = 0;
+
+    for (processing_time, weight) in items {
+        if processing_time <= capacity {
+            capacity -= processing_time;
+            bound += weight;
+        } else {
+            let fraction = weight as u128 * capacity as u128 / processing_time as u128;
+            bound += fraction as u64;
+            break;
+        }
+    }
+
+    bound
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::read_test_file;
+    use crate::{list_algorithm, tresoldi, vns, BranchAndBound, ConflictGraph, Objective, Task};
+
+    #[test]
+    fn test_bounds() {
+        for file in std::fs::read_dir("src/test").expect("Error reading test directory") {
+            let instance = read_test_file(file.unwrap().path()).expect("Error reading test file");
+            let bound = upper_bound(&instance);
+
+            assert!(bound <= knapsack_bound(&instance));
+            assert!(bound <= clique_bound(&instance));
+            assert!(list_algorithm(&instance).calculate_score() <= bound);
+            assert!(vns(&instance).calculate_score() <= bound);
+            assert!(tresoldi(&instance).calculate_score() <= bound);
+        }
+    }
+
+    #[test]
+    fn test_optimal_within_bound() {
+        let instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        let solution = BranchAndBound::default().solve(&instance);
+        let bound = solution.upper_bound.expect("Weight objective has a bound");
+        let evaluation = Evaluation::with_bound(&solution.schedule, bound);
+
+        assert!(bound <= upper_bound(&instance));
+        assert_eq!(evaluation.gap, Some(0.0));
+    }
+
+    #[test]
+    fn test_minimised_without_bound() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.objective = Objective::Makespan;
+        let schedule = list_algorithm(&instance);
+        let evaluation = Evaluation::new(&schedule);
+
+        assert_eq!(evaluation.upper_bound, None);
+        assert_eq!(evaluation.gap, None);
+        assert_eq!(evaluation.objective_value, schedule.makespan());
+    }
+
+    #[test]
+    fn test_overlapping_windows() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.processors = 1;
+        instance.deadline = 300;
+        instance.tasks.truncate(1);
+        instance.graph = ConflictGraph::from_iter([]);
+        instance.unavailability = [(0, 0, 100), (0, 50, 150), (0, 150, 160), (0, 250, 400)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(instance.machine_capacity(0), 90);
+        instance.unavailability = [(0, 0, 100), (0, 50, 150)].into_iter().collect();
+        assert_eq!(instance.machine_capacity(0), 150);
+
+        let solution = BranchAndBound::default().solve(&instance);
+        assert!(solution.is_optimal());
+        assert_eq!(
+            solution.schedule.calculate_score(),
+            instance.tasks[0].weight
+        );
+        assert!(upper_bound(&instance) >= solution.schedule.calculate_score());
+    }
+
+    #[test]
+    fn test_large_products() {
+        let tasks = vec![Task::new(1 << 40, 1 << 40), Task::new(1 << 40, 1 << 39)];
+        let instance = Instance::new(1, 1 << 41, tasks, ConflictGraph::from_iter([]));
+
+        assert_eq!(knapsack_bound(&instance), (1 << 40) + (1 << 39));
+    }
+
+    #[test]
+    fn test_optimality_gap() {
+        assert_eq!(optimality_gap(0, 0), 0.0);
+        assert_eq!(optimality_gap(5, 10), 0.5);
+        assert_eq!(optimality_gap(10, 10), 0.0);
+    }
+}
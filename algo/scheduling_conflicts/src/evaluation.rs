@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::bounds::Evaluation;
+use crate::{Instance, Schedule, ScheduleInfo, Violation};
+
+/// Evaluation of a plan with the violated constraints and the utilisation of machines.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PlanEvaluation {
+    #[serde(flatten)]
+    pub evaluation: Evaluation,
+    pub violations: Vec<Violation>,
+    /// Fraction of the available time of every machine within the deadline spent processing tasks.
+    pub utilisation: Vec<f64>,
+}
+
+/// Evaluates the plan of the instance given as a schedule info for every task.
+/// Tasks without a schedule info aren't scheduled, including the frozen ones.
+/// The plan is scored even when it violates the constraints.
+pub fn evaluate_plan(instance: &Instance, plan: &[Option<ScheduleInfo>]) -> PlanEvaluation {
+    let mut schedule = Schedule::empty(instance);
+    let mut violations = Vec::new();
+
+    for (task, info) in plan.iter().enumerate() {
+        match info {
+            Some(_) if task >= instance.tasks.len() => {
+                violations.push(Violation::TaskOutOfRange { task })
+            }
+            Some(info) => schedule.schedule(task, *info),
+            None => {}
+        }
+    }
+
+    violations.extend(schedule.violations());
+
+    PlanEvaluation {
+        evaluation: Evaluation::new(&schedule),
+        violations,
+        utilisation: utilisation(&schedule),
+    }
+}
+
+/// Returns the fraction of the available time of every machine within the deadline
+/// spent processing tasks of the schedule.
+pub fn utilisation(schedule: &Schedule) -> Vec<f64> {
+    let instance = schedule.instance();
+
+    (0..instance.processors)
+        .map(|machine| {
+            let capacity = instance.machine_capacity(machine);
+            let busy: u64 = schedule
+                .machine_tasks(machine)
+                .map(|(start_time, task)| {
+                    let end_time = start_time + instance.processing_time(task, machine);
+                    end_time.min(instance.deadline).saturating_sub(start_time)
+                })
+                .sum();
+
+            if capacity == 0 {
+                0.0
+            } else {
+                busy as f64 / capacity as f64
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::list_algorithm;
+    use crate::schedulers::test_utils::read_test_file;
+
+    #[test]
+    fn test_evaluate_schedule() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let schedule = list_algorithm(&instance);
+        let plan: Vec<Option<ScheduleInfo>> = (0..instance.tasks.len())
+            .map(|task| schedule.get_schedule(task).copied())
+            .collect();
+
+        let evaluation = evaluate_plan(&instance, &plan);
+
+        assert_eq!(evaluation.evaluation, Evaluation::new(&schedule));
+        assert!(evaluation.violations.is_empty());
+        assert_eq!(evaluation.utilisation.len(), instance.processors);
+        assert!(evaluation
+            .utilisation
+            .iter()
+            .all(|&utilisation| utilisation > 0.0 && utilisation <= 1.0));
+    }
+
+    #[test]
+    fn test_evaluate_edited_plan() {
+        let instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        let mut plan = vec![None; instance.tasks.len() + 1];
+        plan[0] = Some(ScheduleInfo::new(0, 0));
+        plan[5] = Some(ScheduleInfo::new(100, 1));
+        plan[instance.tasks.len()] = Some(ScheduleInfo::new(0, 2));
+
+        let evaluation = evaluate_plan(&instance, &plan);
+
+        assert_eq!(evaluation.evaluation.score, 19);
+        assert_eq!(
+            evaluation.violations,
+            vec![
+                Violation::TaskOutOfRange { task: 11 },
+                Violation::ConflictOverlap {
+                    first: 0,
+                    second: 5
+                },
+            ]
+        );
+        assert_eq!(
+            evaluation.utilisation,
+            vec![150.0 / 480.0, 200.0 / 480.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,331 @@
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{ConflictGraph, Instance, Task};
+
+/// Distribution of generated integer values. Every sample is at least one.
+///
+/// It's parsed from `constant:VALUE`, `uniform:MIN:MAX` or `exponential:MEAN`.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Distribution {
+    /// Always the same value.
+    Constant(u64),
+    /// Uniform over the inclusive range.
+    Uniform { min: u64, max: u64 },
+    /// Exponential with the given mean rounded to an integer.
+    Exponential { mean: f64 },
+}
+
+impl Distribution {
+    /// Draws a value from the distribution.
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> u64 {
+        let value = match *self {
+            Distribution::Constant(value) => value,
+            Distribution::Uniform { min, max } => rng.gen_range(min..=max.max(min)),
+            Distribution::Exponential { mean } => {
+                (-mean * (1.0 - rng.gen::<f64>()).ln()).round() as u64
+            }
+        };
+        value.max(1)
+    }
+}
+
+/// Model of the generated conflict graph.
+///
+/// It's parsed from `er:DENSITY`, `interval:LENGTH` or `cliques:CLUSTERS:DENSITY`
+/// with densities between zero and one.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum ConflictModel {
+    /// Erdős–Rényi graph, every pair of tasks conflicts with the given probability.
+    ErdosRenyi { density: f64 },
+    /// Interval graph. Every task gets an interval of the given length placed uniformly
+    /// in the unit interval and tasks with overlapping intervals conflict.
+    Interval { length: f64 },
+    /// Tasks are split uniformly into clusters. Tasks of a cluster form a clique
+    /// and tasks of different clusters conflict with the given probability.
+    ClusteredCliques { clusters: usize, density: f64 },
+}
+
+impl ConflictModel {
+    /// Draws the conflicts of the given number of tasks.
+    pub fn sample<R: Rng>(&self, tasks: usize, rng: &mut R) -> ConflictGraph {
+        let mut conflicts = Vec::new();
+
+        match *self {
+            ConflictModel::ErdosRenyi { density } => {
+                for first in 0..tasks {
+                    for second in first + 1..tasks {
+                        if rng.gen_bool(density.clamp(0.0, 1.0)) {
+                            conflicts.push((first, second));
+                        }
+                    }
+                }
+            }
+            ConflictModel::Interval { length } => {
+                let mut starts: Vec<(f64, usize)> = (0..tasks)
+                    .map(|task| (rng.gen::<f64>() * (1.0 - length).max(0.0), task))
+                    .collect();
+                starts.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+                for (index, &(start, first)) in starts.iter().enumerate() {
+                    conflicts.extend(
+                        starts[index + 1..]
+                            .iter()
+                            .take_while(|&&(other_start, _)| other_start < start + length)
+                            .map(|&(_, second)| (first, second)),
+                    );
+                }
+            }
+            ConflictModel::ClusteredCliques { clusters, density } => {
+                let clusters: Vec<usize> = (0..tasks)
+                    .map(|_| rng.gen_range(0..clusters.max(1)))
+                    .collect();
+
+                for first in 0..tasks {
+                    for second in first + 1..tasks {
+                        if clusters[first] == clusters[second]
+                            || rng.gen_bool(density.clamp(0.0, 1.0))
+                        {
+                            conflicts.push((first, second));
+                        }
+                    }
+                }
+            }
+        }
+
+        conflicts.into_iter().collect()
+    }
+}
+
+/// An invalid description of a distribution or a conflict model.
+#[derive(Clone, Debug, Eq, Error, PartialEq)]
+#[error("invalid {kind} `{value}`")]
+pub struct ParseModelError {
+    kind: &'static str,
+    value: String,
+}
+
+/// Splits the description into its name and numeric parameters.
+/// Parameters have to be finite and non-negative.
+fn parse_parts(kind: &'static str, value: &str) -> Result<(String, Vec<f64>), ParseModelError> {
+    let error = || ParseModelError {
+        kind,
+        value: value.to_string(),
+    };
+    let mut parts = value.split(':');
+    let name = parts.next().ok_or_else(error)?.to_lowercase();
+    let parameters = parts
+        .map(|part| {
+            part.parse::<f64>()
+                .ok()
+                .filter(|parameter| parameter.is_finite() && *parameter >= 0.0)
+                .ok_or_else(error)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((name, parameters))
+}
+
+impl FromStr for Distribution {
+    type Err = ParseModelError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, parameters) = parse_parts("distribution", value)?;
+
+        match (name.as_str(), parameters.as_slice()) {
+            ("constant", &[value]) => Ok(Distribution::Constant(value as u64)),
+            ("uniform", &[min, max]) => Ok(Distribution::Uniform {
+                min: min as u64,
+                max: max as u64,
+            }),
+            ("exponential", &[mean]) => Ok(Distribution::Exponential { mean }),
+            _ => Err(ParseModelError {
+                kind: "distribution",
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+impl FromStr for ConflictModel {
+    type Err = ParseModelError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (name, parameters) = parse_parts("conflict model", value)?;
+
+        match (name.as_str(), parameters.as_slice()) {
+            ("er", &[density]) if density <= 1.0 => Ok(ConflictModel::ErdosRenyi { density }),
+            ("interval", &[length]) => Ok(ConflictModel::Interval { length }),
+            ("cliques", &[clusters, density]) if density <= 1.0 => {
+                Ok(ConflictModel::ClusteredCliques {
+                    clusters: clusters as usize,
+                    density,
+                })
+            }
+            _ => Err(ParseModelError {
+                kind: "conflict model",
+                value: value.to_string(),
+            }),
+        }
+    }
+}
+
+/// Generator of random instances.
+/// Generators with the same seed produce the same instance.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Generator {
+    /// Number of tasks.
+    pub tasks: usize,
+    /// Number of identical processors.
+    pub processors: usize,
+    /// Deadline as a fraction of the time needed to process all tasks on the processors.
+    /// Smaller values make tighter instances where fewer tasks can be scheduled.
+    pub tightness: f64,
+    /// Distribution of the processing times.
+    pub processing_time: Distribution,
+    /// Distribution of the weights.
+    pub weight: Distribution,
+    /// Model of the conflict graph.
+    pub conflicts: ConflictModel,
+    /// Seed of the random generator. A random seed is used if it's not given.
+    pub seed: Option<u64>,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            tasks: 50,
+            processors: 3,
+            tightness: 0.5,
+            processing_time: Distribution::Uniform { min: 10, max: 200 },
+            weight: Distribution::Uniform { min: 1, max: 10 },
+            conflicts: ConflictModel::ErdosRenyi { density: 0.1 },
+            seed: None,
+        }
+    }
+}
+
+impl Generator {
+    /// Generates a new instance.
+    pub fn generate(&self) -> Instance {
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let tasks: Vec<Task> = (0..self.tasks)
+            .map(|_| {
+                let processing_time = self.processing_time.sample(&mut rng);
+                Task::new(processing_time, self.weight.sample(&mut rng))
+            })
+            .collect();
+        let graph = self.conflicts.sample(self.tasks, &mut rng);
+
+        let total: u64 = tasks.iter().map(|task| task.processing_time).sum();
+        let deadline = (total as f64 * self.tightness / self.processors.max(1) as f64).ceil();
+
+        Instance::new(self.processors, (deadline as u64).max(1), tasks, graph)
+    }
+}
+
+/// Generates an instance with the default parameters and the given seed.
+pub fn generate(seed: u64) -> Instance {
+    Generator {
+        seed: Some(seed),
+        ..Generator::default()
+    }
+    .generate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialization;
+
+    #[test]
+    fn test_generate_seeded() {
+        for conflicts in [
+            ConflictModel::ErdosRenyi { density: 0.2 },
+            ConflictModel::Interval { length: 0.1 },
+            ConflictModel::ClusteredCliques {
+                clusters: 4,
+                density: 0.05,
+            },
+        ] {
+            let generator = Generator {
+                tasks: 30,
+                conflicts,
+                seed: Some(17),
+                ..Generator::default()
+            };
+
+            let first = generator.generate();
+            let second = generator.generate();
+
+            assert_eq!(first.validate(), Ok(()));
+            assert_eq!(first.tasks.len(), 30);
+            assert_eq!(
+                serialization::to_string(&first).expect("Error serializing instance"),
+                serialization::to_string(&second).expect("Error serializing instance")
+            );
+        }
+    }
+
+    #[test]
+    fn test_conflict_models() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let conflicts = |graph: ConflictGraph| graph.iter().count();
+
+        assert_eq!(
+            conflicts(ConflictModel::ErdosRenyi { density: 1.0 }.sample(10, &mut rng)),
+            45
+        );
+        assert_eq!(
+            conflicts(ConflictModel::Interval { length: 0.0 }.sample(10, &mut rng)),
+            0
+        );
+        assert_eq!(
+            conflicts(ConflictModel::Interval { length: 1.0 }.sample(10, &mut rng)),
+            45
+        );
+        assert_eq!(
+            conflicts(
+                ConflictModel::ClusteredCliques {
+                    clusters: 1,
+                    density: 0.0
+                }
+                .sample(10, &mut rng)
+            ),
+            45
+        );
+    }
+
+    #[test]
+    fn test_parse_models() {
+        assert_eq!(
+            "uniform:1:10".parse(),
+            Ok(Distribution::Uniform { min: 1, max: 10 })
+        );
+        assert_eq!("constant:5".parse(), Ok(Distribution::Constant(5)));
+        assert_eq!(
+            "cliques:3:0.1".parse(),
+            Ok(ConflictModel::ClusteredCliques {
+                clusters: 3,
+                density: 0.1
+            })
+        );
+        assert!("uniform:1".parse::<Distribution>().is_err());
+        assert!("er:dense".parse::<ConflictModel>().is_err());
+        assert!("er:nan".parse::<ConflictModel>().is_err());
+        assert!("er:1.5".parse::<ConflictModel>().is_err());
+        assert!("cliques:3:nan".parse::<ConflictModel>().is_err());
+        assert!("cliques:inf:0.1".parse::<ConflictModel>().is_err());
+        assert!("interval:-0.1".parse::<ConflictModel>().is_err());
+        assert!("exponential:inf".parse::<Distribution>().is_err());
+        assert!("uniform:-1:10".parse::<Distribution>().is_err());
+    }
+}
@@ -1,26 +1,47 @@
+pub mod bounds;
+pub mod evaluation;
+pub mod generator;
+mod objective;
 mod problem;
 pub mod schedulers;
 pub mod serialization;
 mod solution;
 pub mod util;
 
+pub use objective::*;
 pub use problem::*;
 pub use schedulers::*;
 pub use solution::*;
 
-/// Runs the given scheduler on the instance read from stdin and writes the schedule to stdout.
-/// Also writes the score to stdout.
-/// Returns an error if the instance could not be read or the schedule could not be written.
+use std::error::Error;
+
+/// Runs the given scheduler on the instance read from stdin and writes the schedule to stdout
+/// followed by a line with the score.
+/// With the `--bounds` argument it also writes the upper bound of the instance
+/// and the optimality gap on their own lines for the weight objective.
+/// Returns an error if the instance could not be read or is invalid
+/// or the schedule could not be written.
 ///
 /// # Panics
 ///  - If the schedule is invalid in debug mode.
-pub fn run_experiment_from_stdin<T: Scheduler>(scheduler: T) -> serialization::Result<()> {
+pub fn run_experiment_from_stdin<T: Scheduler>(scheduler: T) -> Result<(), Box<dyn Error>> {
+    let bounds = std::env::args().skip(1).any(|arg| arg == "--bounds");
     let instance: Instance = serialization::from_stdin()?;
+    instance.validate()?;
     let schedule = scheduler.schedule(&instance);
 
-    debug_assert!(schedule.verify(), "Schedule is invalid: {schedule:?}");
+    debug_assert!(
+        schedule.verify(),
+        "Schedule is invalid: {:?}",
+        schedule.violations()
+    );
 
     serialization::to_stdout(&schedule)?;
-    println!("{}", schedule.calculate_score());
+    let evaluation = bounds::Evaluation::new(&schedule);
+    println!("{}", evaluation.score);
+    if let (true, Some(upper_bound), Some(gap)) = (bounds, evaluation.upper_bound, evaluation.gap) {
+        println!("{upper_bound}");
+        println!("{gap:.4}");
+    }
     Ok(())
 }
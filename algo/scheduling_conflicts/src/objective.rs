@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Instance, Schedule};
+
+/// The objective optimised by schedulers.
+///
+/// The weight objective leaves tasks that can't finish by their due dates unscheduled.
+/// The other objectives are minimised, every task has to be scheduled
+/// and due dates only count towards the weighted tardiness.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Objective {
+    /// Maximises the total weight of tasks finished by their due dates.
+    #[default]
+    Weight,
+    /// Minimises the completion time of the last task.
+    Makespan,
+    /// Minimises the total weighted completion time of the tasks.
+    WeightedCompletion,
+    /// Minimises the total weighted tardiness of the tasks past their due dates.
+    WeightedTardiness,
+}
+
+impl Objective {
+    /// Returns whether the objective is minimised, so every task has to be scheduled
+    /// and tasks can finish after their due dates.
+    pub fn is_minimised(self) -> bool {
+        self != Objective::Weight
+    }
+
+    /// Returns the value of the objective for the schedule.
+    /// It's the weight of tasks on time or the cost of the schedule for minimised objectives.
+    /// Unscheduled tasks don't count.
+    pub fn value(self, schedule: &Schedule) -> u64 {
+        match self {
+            Objective::Weight => schedule.calculate_score(),
+            Objective::Makespan => schedule.makespan(),
+            _ => self.score(schedule).unsigned_abs(),
+        }
+    }
+
+    /// Returns the score of the schedule. Higher is better,
+    /// so it's the weight of tasks on time or the negated cost for minimised objectives.
+    pub fn score(self, schedule: &Schedule) -> i64 {
+        let instance = schedule.instance();
+        let score: i64 = (0..instance.tasks.len())
+            .map(|task| self.task_score(instance, task, schedule.completion_time(task)))
+            .sum();
+        score - self.schedule_cost(schedule)
+    }
+
+    /// Returns the part of the score contributed by the task completed at the given time.
+    pub(crate) fn task_score(
+        self,
+        instance: &Instance,
+        task: usize,
+        completion_time: Option<u64>,
+    ) -> i64 {
+        let Some(completion_time) = completion_time else {
+            return 0;
+        };
+        let weight = instance.tasks[task].weight;
+
+        match self {
+            Objective::Weight if completion_time <= instance.due_date(task) => weight as i64,
+            Objective::Weight | Objective::Makespan => 0,
+            Objective::WeightedCompletion => -((weight * completion_time) as i64),
+            Objective::WeightedTardiness => {
+                let tardiness = completion_time.saturating_sub(instance.due_date(task));
+                -((weight * tardiness) as i64)
+            }
+        }
+    }
+
+    /// Returns the part of the cost that isn't a sum over the tasks.
+    pub(crate) fn schedule_cost(self, schedule: &Schedule) -> i64 {
+        match self {
+            Objective::Makespan => schedule.makespan() as i64,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::read_test_file;
+    use crate::{list_algorithm, ScheduleInfo};
+
+    #[test]
+    fn test_objective_values() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.deadline = 1_000;
+        instance.objective = Objective::WeightedTardiness;
+
+        let mut schedule = Schedule::empty(&instance);
+        schedule.schedule(0, ScheduleInfo::new(990, 0));
+        schedule.schedule(1, ScheduleInfo::new(0, 1));
+
+        let completion = |task| schedule.completion_time(task).expect("Task is scheduled");
+        let weight = |task: usize| instance.tasks[task].weight;
+
+        assert_eq!(Objective::Makespan.value(&schedule), completion(0));
+        assert_eq!(
+            Objective::WeightedCompletion.value(&schedule),
+            weight(0) * completion(0) + weight(1) * completion(1)
+        );
+        assert_eq!(
+            Objective::WeightedTardiness.value(&schedule),
+            weight(0) * (completion(0) - 1_000)
+        );
+        assert_eq!(
+            Objective::WeightedTardiness.score(&schedule),
+            -(Objective::WeightedTardiness.value(&schedule) as i64)
+        );
+        assert_eq!(Objective::Weight.value(&schedule), weight(1));
+    }
+
+    #[test]
+    fn test_objective_serialization() {
+        let mut instance =
+            read_test_file("src/test/test10_frozen.txt").expect("Error reading test file");
+        instance.objective = Objective::WeightedCompletion;
+
+        let text = crate::serialization::to_string(&instance).expect("Error serializing instance");
+        let read: Instance = crate::serialization::deserialize(&mut text.as_bytes())
+            .expect("Error deserializing instance");
+
+        assert_eq!(read.objective, Objective::WeightedCompletion);
+        assert_eq!(read.frozen.iter().count(), instance.frozen.iter().count());
+    }
+
+    #[test]
+    fn test_minimised_objectives() {
+        let mut instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        instance.deadline = 100;
+
+        for objective in [
+            Objective::Makespan,
+            Objective::WeightedCompletion,
+            Objective::WeightedTardiness,
+        ] {
+            instance.objective = objective;
+            let schedule = list_algorithm(&instance);
+
+            assert_eq!(schedule.violations(), vec![]);
+            assert!((0..instance.tasks.len()).all(|task| schedule.get_schedule(task).is_some()));
+        }
+    }
+}
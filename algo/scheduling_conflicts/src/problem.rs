@@ -1,13 +1,31 @@
-use ahash::{HashSet, HashSetExt};
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{Objective, ScheduleInfo};
 
 /// A task. Contains the processing time and weight of the task.
+/// Optionally contains the release time and due date of the task.
 #[non_exhaustive]
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Serialize, PartialEq)]
 pub struct Task {
     pub processing_time: u64,
     pub weight: u64,
+    pub release_time: Option<u64>,
+    pub due_date: Option<u64>,
+}
+
+impl Task {
+    /// Creates a new task without a release time and due date.
+    pub fn new(processing_time: u64, weight: u64) -> Self {
+        Self {
+            processing_time,
+            weight,
+            release_time: None,
+            due_date: None,
+        }
+    }
 }
 
 /// A conflict between two tasks described by their indices.
@@ -36,6 +54,25 @@ impl ConflictGraph {
 
         self.edges.get(task).unwrap_or(&EMPTY)
     }
+
+    /// Returns every conflict once as a pair of tasks with the smaller index first.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges.iter().enumerate().flat_map(|(task, conflicts)| {
+            conflicts
+                .iter()
+                .filter(move |&&other| other >= task)
+                .map(move |&other| (task, other))
+        })
+    }
+}
+
+impl FromIterator<(usize, usize)> for ConflictGraph {
+    fn from_iter<T: IntoIterator<Item = (usize, usize)>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|(first, second)| Conflict(first, second))
+            .collect::<Vec<_>>()
+            .into()
+    }
 }
 
 impl From<Vec<Conflict>> for ConflictGraph {
@@ -67,10 +104,654 @@ impl From<ConflictGraph> for Vec<Conflict> {
             }
         }
 
+        result.sort_unstable_by_key(|&Conflict(first, second)| (first, second));
+        result
+    }
+}
+
+/// A task that can be processed on a machine described by their indices.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct Assignment(usize, usize);
+
+/// Machine eligibility of tasks. Tasks without any eligible machines listed can run on every machine.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<Assignment>", into = "Vec<Assignment>")]
+pub struct Eligibility {
+    machines: Vec<HashSet<usize>>,
+}
+
+impl Eligibility {
+    /// Returns whether the given task can be processed on the given machine.
+    pub fn is_eligible(&self, task: usize, machine: usize) -> bool {
+        self.machines
+            .get(task)
+            .filter(|machines| !machines.is_empty())
+            .map(|machines| machines.contains(&machine))
+            .unwrap_or(true)
+    }
+
+    /// Returns whether every task can be processed on every machine.
+    pub fn is_empty(&self) -> bool {
+        self.machines.iter().all(HashSet::is_empty)
+    }
+}
+
+impl FromIterator<(usize, usize)> for Eligibility {
+    fn from_iter<T: IntoIterator<Item = (usize, usize)>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|(task, machine)| Assignment(task, machine))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl From<Vec<Assignment>> for Eligibility {
+    fn from(assignments: Vec<Assignment>) -> Self {
+        let mut machines = Vec::new();
+
+        for Assignment(task, machine) in assignments {
+            while machines.len() <= task {
+                machines.push(HashSet::new());
+            }
+
+            machines[task].insert(machine);
+        }
+
+        Eligibility { machines }
+    }
+}
+
+impl From<Eligibility> for Vec<Assignment> {
+    fn from(eligibility: Eligibility) -> Self {
+        let mut result = Vec::new();
+
+        for (task, machines) in eligibility.machines.into_iter().enumerate() {
+            let mut machines: Vec<usize> = machines.into_iter().collect();
+            machines.sort_unstable();
+            result.extend(
+                machines
+                    .into_iter()
+                    .map(|machine| Assignment(task, machine)),
+            );
+        }
+
+        result
+    }
+}
+
+/// A processing time of a task on a machine described by their indices.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct MachineTime(usize, usize, u64);
+
+/// Machine dependent processing times of tasks.
+/// Tasks without a time listed for a machine use their own processing time.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<MachineTime>", into = "Vec<MachineTime>")]
+pub struct ProcessingTimes {
+    times: Vec<HashMap<usize, u64>>,
+}
+
+impl ProcessingTimes {
+    /// Returns the processing time of the given task on the given machine if it's listed.
+    pub fn get(&self, task: usize, machine: usize) -> Option<u64> {
+        self.times
+            .get(task)
+            .and_then(|times| times.get(&machine))
+            .copied()
+    }
+
+    /// Returns whether no machine dependent processing time is listed.
+    pub fn is_empty(&self) -> bool {
+        self.times.iter().all(HashMap::is_empty)
+    }
+}
+
+impl FromIterator<(usize, usize, u64)> for ProcessingTimes {
+    fn from_iter<T: IntoIterator<Item = (usize, usize, u64)>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|(task, machine, time)| MachineTime(task, machine, time))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl From<Vec<MachineTime>> for ProcessingTimes {
+    fn from(machine_times: Vec<MachineTime>) -> Self {
+        let mut times = Vec::new();
+
+        for MachineTime(task, machine, time) in machine_times {
+            while times.len() <= task {
+                times.push(HashMap::new());
+            }
+
+            times[task].insert(machine, time);
+        }
+
+        ProcessingTimes { times }
+    }
+}
+
+impl From<ProcessingTimes> for Vec<MachineTime> {
+    fn from(processing_times: ProcessingTimes) -> Self {
+        let mut result = Vec::new();
+
+        for (task, times) in processing_times.times.into_iter().enumerate() {
+            let mut times: Vec<(usize, u64)> = times.into_iter().collect();
+            times.sort_unstable();
+            result.extend(
+                times
+                    .into_iter()
+                    .map(|(machine, time)| MachineTime(task, machine, time)),
+            );
+        }
+
         result
     }
 }
 
+/// A setup time needed between two consecutive tasks on a machine described by their indices.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct Setup(usize, usize, u64);
+
+/// Sequence dependent setup times between consecutive tasks on the same machine.
+/// Pairs of tasks without a listed setup time need no setup.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<Setup>", into = "Vec<Setup>")]
+pub struct SetupTimes {
+    times: Vec<HashMap<usize, u64>>,
+}
+
+impl SetupTimes {
+    /// Returns the setup time needed when the second task directly follows the first one.
+    pub fn get(&self, first: usize, second: usize) -> u64 {
+        self.times
+            .get(first)
+            .and_then(|times| times.get(&second))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns whether no setup time is listed.
+    pub fn is_empty(&self) -> bool {
+        self.times.iter().all(HashMap::is_empty)
+    }
+}
+
+impl FromIterator<(usize, usize, u64)> for SetupTimes {
+    fn from_iter<T: IntoIterator<Item = (usize, usize, u64)>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|(first, second, time)| Setup(first, second, time))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl From<Vec<Setup>> for SetupTimes {
+    fn from(setups: Vec<Setup>) -> Self {
+        let mut times = Vec::new();
+
+        for Setup(first, second, time) in setups {
+            while times.len() <= first {
+                times.push(HashMap::new());
+            }
+
+            times[first].insert(second, time);
+        }
+
+        SetupTimes { times }
+    }
+}
+
+impl From<SetupTimes> for Vec<Setup> {
+    fn from(setup_times: SetupTimes) -> Self {
+        let mut result = Vec::new();
+
+        for (first, times) in setup_times.times.into_iter().enumerate() {
+            let mut times: Vec<(usize, u64)> = times.into_iter().collect();
+            times.sort_unstable();
+            result.extend(
+                times
+                    .into_iter()
+                    .map(|(second, time)| Setup(first, second, time)),
+            );
+        }
+
+        result
+    }
+}
+
+/// A time window `[start, end)` a machine is unavailable in described by the machine index.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct BlockedWindow(usize, u64, u64);
+
+/// Unavailability of machines, e.g. breaks, shifts or maintenance.
+/// Machines without any blocked windows listed are always available.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<BlockedWindow>", into = "Vec<BlockedWindow>")]
+pub struct Unavailability {
+    windows: Vec<Vec<(u64, u64)>>,
+}
+
+impl Unavailability {
+    /// Returns the blocked windows of the given machine ordered by their start.
+    pub fn windows(&self, machine: usize) -> &[(u64, u64)] {
+        self.windows.get(machine).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns whether the given machine is available in the whole `[start, end)` interval.
+    pub fn is_available(&self, machine: usize, start: u64, end: u64) -> bool {
+        start == end
+            || self
+                .windows(machine)
+                .iter()
+                .all(|&(blocked_start, blocked_end)| end <= blocked_start || blocked_end <= start)
+    }
+
+    /// Returns whether no blocked window is listed.
+    pub fn is_empty(&self) -> bool {
+        self.windows.iter().all(Vec::is_empty)
+    }
+}
+
+impl FromIterator<(usize, u64, u64)> for Unavailability {
+    fn from_iter<T: IntoIterator<Item = (usize, u64, u64)>>(iter: T) -> Self {
+        iter.into_iter()
+            .map(|(machine, start, end)| BlockedWindow(machine, start, end))
+            .collect::<Vec<_>>()
+            .into()
+    }
+}
+
+impl From<Vec<BlockedWindow>> for Unavailability {
+    fn from(blocked_windows: Vec<BlockedWindow>) -> Self {
+        let mut windows = Vec::new();
+
+        for BlockedWindow(machine, start, end) in blocked_windows {
+            while windows.len() <= machine {
+                windows.push(Vec::new());
+            }
+
+            windows[machine].push((start, end));
+        }
+
+        for machine_windows in &mut windows {
+            machine_windows.sort_unstable();
+        }
+
+        Unavailability { windows }
+    }
+}
+
+impl From<Unavailability> for Vec<BlockedWindow> {
+    fn from(unavailability: Unavailability) -> Self {
+        let mut result = Vec::new();
+
+        for (machine, windows) in unavailability.windows.into_iter().enumerate() {
+            result.extend(
+                windows
+                    .into_iter()
+                    .map(|(start, end)| BlockedWindow(machine, start, end)),
+            );
+        }
+
+        result
+    }
+}
+
+/// A task fixed to the machine at the start time described by the task index, machine index and start time.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct FrozenTask(usize, usize, u64);
+
+/// Frozen assignments of tasks, e.g. tasks that already started on the shop floor.
+/// Schedulers keep frozen tasks exactly where they are and schedule only the remaining tasks.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<FrozenTask>", into = "Vec<FrozenTask>")]
+pub struct Frozen {
+    assignments: Vec<Option<ScheduleInfo>>,
+    machines: Vec<Vec<(u64, usize)>>,
+}
+
+impl Frozen {
+    /// Returns the frozen assignment of the given task.
+    pub fn get(&self, task: usize) -> Option<ScheduleInfo> {
+        self.assignments.get(task).copied().flatten()
+    }
+
+    /// Returns the start times and ids of tasks frozen on the given machine ordered by their start.
+    pub fn machine_tasks(&self, machine: usize) -> &[(u64, usize)] {
+        self.machines.get(machine).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns an iterator over the frozen tasks with their assignments.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, ScheduleInfo)> + '_ {
+        self.assignments
+            .iter()
+            .enumerate()
+            .filter_map(|(task, info)| info.map(|info| (task, info)))
+    }
+
+    /// Returns whether no task is frozen.
+    pub fn is_empty(&self) -> bool {
+        self.machines.iter().all(Vec::is_empty)
+    }
+}
+
+impl FromIterator<(usize, ScheduleInfo)> for Frozen {
+    fn from_iter<T: IntoIterator<Item = (usize, ScheduleInfo)>>(iter: T) -> Self {
+        let mut assignments = Vec::new();
+        let mut machines = Vec::new();
+
+        for (task, info) in iter {
+            while assignments.len() <= task {
+                assignments.push(None);
+            }
+
+            while machines.len() <= info.processor {
+                machines.push(Vec::new());
+            }
+
+            if let Some(previous) = assignments[task].replace(info) {
+                machines[previous.processor].retain(|&(_, other)| other != task);
+            }
+            machines[info.processor].push((info.start_time, task));
+        }
+
+        for machine_tasks in &mut machines {
+            machine_tasks.sort_unstable();
+        }
+
+        Frozen {
+            assignments,
+            machines,
+        }
+    }
+}
+
+impl From<Vec<FrozenTask>> for Frozen {
+    fn from(frozen_tasks: Vec<FrozenTask>) -> Self {
+        frozen_tasks
+            .into_iter()
+            .map(|FrozenTask(task, machine, start_time)| {
+                (task, ScheduleInfo::new(start_time, machine))
+            })
+            .collect()
+    }
+}
+
+impl From<Frozen> for Vec<FrozenTask> {
+    fn from(frozen: Frozen) -> Self {
+        frozen
+            .iter()
+            .map(|(task, info)| FrozenTask(task, info.processor, info.start_time))
+            .collect()
+    }
+}
+
+/// A resource needed by a task described by the task index and the resource index.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct Requirement(usize, usize);
+
+/// Shared resources of tasks, e.g. jigs or operators. Tasks needing the same resource conflict.
+/// Conflicts are expanded lazily, so a resource needed by many tasks isn't stored as all their pairs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<Requirement>", into = "Vec<Requirement>")]
+pub struct Resources {
+    resources: Vec<Vec<usize>>,
+    users: Vec<Vec<usize>>,
+}
+
+impl Resources {
+    /// Returns the resources needed by the given task in increasing order.
+    pub fn resources(&self, task: usize) -> &[usize] {
+        self.resources.get(task).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the tasks needing the given resource in increasing order.
+    pub fn users(&self, resource: usize) -> &[usize] {
+        self.users.get(resource).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns whether the given tasks need a common resource.
+    pub fn share(&self, first: usize, second: usize) -> bool {
+        let second_resources = self.resources(second);
+        self.resources(first)
+            .iter()
+            .any(|resource| second_resources.binary_search(resource).is_ok())
+    }
+
+    /// Returns whether no task needs a resource.
+    pub fn is_empty(&self) -> bool {
+        self.users.iter().all(Vec::is_empty)
+    }
+}
+
+impl FromIterator<(usize, usize)> for Resources {
+    fn from_iter<T: IntoIterator<Item = (usize, usize)>>(iter: T) -> Self {
+        let mut resources = Vec::new();
+        let mut users = Vec::new();
+
+        for (task, resource) in iter {
+            while resources.len() <= task {
+                resources.push(Vec::new());
+            }
+
+            while users.len() <= resource {
+                users.push(Vec::new());
+            }
+
+            resources[task].push(resource);
+            users[resource].push(task);
+        }
+
+        for list in resources.iter_mut().chain(&mut users) {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        Resources { resources, users }
+    }
+}
+
+impl From<Vec<Requirement>> for Resources {
+    fn from(requirements: Vec<Requirement>) -> Self {
+        requirements
+            .into_iter()
+            .map(|Requirement(task, resource)| (task, resource))
+            .collect()
+    }
+}
+
+impl From<Resources> for Vec<Requirement> {
+    fn from(resources: Resources) -> Self {
+        resources
+            .resources
+            .into_iter()
+            .enumerate()
+            .flat_map(|(task, resources)| {
+                resources
+                    .into_iter()
+                    .map(move |resource| Requirement(task, resource))
+            })
+            .collect()
+    }
+}
+
+/// A renewable resource with a number of units available at any time, e.g. a team of operators.
+/// Unlike shared resources, tasks using it only conflict when their total usage exceeds the capacity.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub struct CumulativeResource {
+    /// Name of the resource. The text format needs it without whitespace.
+    pub name: String,
+    pub capacity: u64,
+}
+
+impl CumulativeResource {
+    /// Creates a new cumulative resource.
+    pub fn new(name: impl Into<String>, capacity: u64) -> Self {
+        Self {
+            name: name.into(),
+            capacity,
+        }
+    }
+}
+
+/// Units of a cumulative resource used by a task while it's processed
+/// described by the task index, the resource index and the units.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+struct Demand(usize, usize, u64);
+
+/// Demands of tasks for units of cumulative resources. Repeated demands of a task for a resource add up.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "Vec<Demand>", into = "Vec<Demand>")]
+pub struct Demands {
+    demands: Vec<Vec<(usize, u64)>>,
+}
+
+impl Demands {
+    /// Returns the cumulative resources used by the given task with their units
+    /// in increasing order of the resources.
+    pub fn demands(&self, task: usize) -> &[(usize, u64)] {
+        self.demands.get(task).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns the units of the resource used by the given task.
+    pub fn demand(&self, task: usize, resource: usize) -> u64 {
+        let demands = self.demands(task);
+        demands
+            .binary_search_by_key(&resource, |&(resource, _)| resource)
+            .map_or(0, |index| demands[index].1)
+    }
+
+    /// Returns whether no task uses a cumulative resource.
+    pub fn is_empty(&self) -> bool {
+        self.demands.iter().all(Vec::is_empty)
+    }
+}
+
+impl FromIterator<(usize, usize, u64)> for Demands {
+    fn from_iter<T: IntoIterator<Item = (usize, usize, u64)>>(iter: T) -> Self {
+        let mut demands: Vec<Vec<(usize, u64)>> = Vec::new();
+
+        for (task, resource, units) in iter {
+            while demands.len() <= task {
+                demands.push(Vec::new());
+            }
+
+            if units > 0 {
+                demands[task].push((resource, units));
+            }
+        }
+
+        for list in &mut demands {
+            list.sort_unstable();
+            let mut merged: Vec<(usize, u64)> = Vec::with_capacity(list.len());
+            for &(resource, units) in list.iter() {
+                match merged.last_mut() {
+                    Some(last) if last.0 == resource => last.1 += units,
+                    _ => merged.push((resource, units)),
+                }
+            }
+            *list = merged;
+        }
+
+        Demands { demands }
+    }
+}
+
+impl From<Vec<Demand>> for Demands {
+    fn from(demands: Vec<Demand>) -> Self {
+        demands
+            .into_iter()
+            .map(|Demand(task, resource, units)| (task, resource, units))
+            .collect()
+    }
+}
+
+impl From<Demands> for Vec<Demand> {
+    fn from(demands: Demands) -> Self {
+        demands
+            .demands
+            .into_iter()
+            .enumerate()
+            .flat_map(|(task, demands)| {
+                demands
+                    .into_iter()
+                    .map(move |(resource, units)| Demand(task, resource, units))
+            })
+            .collect()
+    }
+}
+
+/// An inconsistency of an instance found by [`Instance::validate`].
+#[derive(Clone, Copy, Debug, Eq, Error, PartialEq)]
+pub enum InstanceError {
+    /// The instance has no processors to schedule tasks on.
+    #[error("the instance has no processors")]
+    NoProcessors,
+    /// A conflict refers to a task the instance doesn't have.
+    #[error("conflict between tasks {first} and {second} refers to a task out of range")]
+    ConflictOutOfRange { first: usize, second: usize },
+    /// A task conflicts with itself.
+    #[error("task {task} conflicts with itself")]
+    SelfConflict { task: usize },
+    /// A task takes no time on a machine it's eligible for.
+    #[error("task {task} has zero processing time on machine {machine}")]
+    ZeroProcessingTime { task: usize, machine: usize },
+    /// A resource is needed by a task the instance doesn't have.
+    #[error("resource {resource} is needed by task {task} which is out of range")]
+    RequirementOutOfRange { task: usize, resource: usize },
+    /// A task uses a cumulative resource the instance doesn't have or the task is out of range.
+    #[error("demand of task {task} for cumulative resource {resource} is out of range")]
+    DemandOutOfRange { task: usize, resource: usize },
+    /// A task alone uses more units of a cumulative resource than its capacity.
+    #[error("task {task} uses more units of cumulative resource {resource} than its capacity")]
+    DemandExceedsCapacity { task: usize, resource: usize },
+    /// A task can't be processed on any machine although the objective schedules every task.
+    #[error("task {task} can't be processed on any machine")]
+    UnschedulableTask { task: usize },
+    /// A frozen task the instance doesn't have.
+    #[error("frozen task {task} is out of range")]
+    FrozenTaskOutOfRange { task: usize },
+    /// A task is frozen on a processor the instance doesn't have.
+    #[error("task {task} is frozen on processor {processor} which is out of range")]
+    FrozenProcessorOutOfRange { task: usize, processor: usize },
+    /// Two tasks frozen on the same machine overlap including the setup between them.
+    #[error("frozen tasks {first} and {second} overlap on machine {machine}")]
+    FrozenOverlap {
+        machine: usize,
+        first: usize,
+        second: usize,
+    },
+    /// Two conflicting frozen tasks overlap in time.
+    #[error("conflicting frozen tasks {first} and {second} overlap")]
+    FrozenConflict { first: usize, second: usize },
+    /// A frozen task runs on a machine it isn't eligible for.
+    #[error("task {task} is frozen on machine {machine} it isn't eligible for")]
+    FrozenIneligible { task: usize, machine: usize },
+    /// A frozen task overlaps a blocked window of its machine.
+    #[error("task {task} is frozen in a blocked window of machine {machine}")]
+    FrozenBlocked { task: usize, machine: usize },
+    /// Frozen tasks together use more units of a cumulative resource than its capacity.
+    #[error("frozen tasks exceed the capacity of cumulative resource {resource} at time {time}")]
+    FrozenCapacityExceeded { resource: usize, time: u64 },
+    /// An eligibility entry refers to a task or a machine the instance doesn't have.
+    #[error("eligibility of task {task} for machine {machine} is out of range")]
+    EligibilityOutOfRange { task: usize, machine: usize },
+    /// A processing time refers to a task or a machine the instance doesn't have.
+    #[error("processing time of task {task} on machine {machine} is out of range")]
+    ProcessingTimeOutOfRange { task: usize, machine: usize },
+    /// A setup time refers to a task the instance doesn't have.
+    #[error("setup time between tasks {first} and {second} is out of range")]
+    SetupTimeOutOfRange { first: usize, second: usize },
+    /// A blocked window ends before it starts.
+    #[error("blocked window [{start}, {end}) of machine {machine} ends before it starts")]
+    InvalidBlockedWindow {
+        machine: usize,
+        start: u64,
+        end: u64,
+    },
+}
+
 /// An instance of the scheduling problem.
 #[non_exhaustive]
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -79,4 +760,682 @@ pub struct Instance {
     pub deadline: u64,
     pub tasks: Vec<Task>,
     pub graph: ConflictGraph,
+    #[serde(default)]
+    pub eligibility: Eligibility,
+    #[serde(default)]
+    pub processing_times: ProcessingTimes,
+    #[serde(default)]
+    pub setup_times: SetupTimes,
+    #[serde(default)]
+    pub unavailability: Unavailability,
+    #[serde(default)]
+    pub frozen: Frozen,
+    #[serde(default)]
+    pub objective: Objective,
+    #[serde(default)]
+    pub resources: Resources,
+    #[serde(default)]
+    pub cumulative_resources: Vec<CumulativeResource>,
+    #[serde(default)]
+    pub demands: Demands,
+}
+
+impl Instance {
+    /// Creates a new instance of identical processors without frozen tasks
+    /// maximising the weight of tasks on time.
+    pub fn new(processors: usize, deadline: u64, tasks: Vec<Task>, graph: ConflictGraph) -> Self {
+        Self {
+            processors,
+            deadline,
+            tasks,
+            graph,
+            eligibility: Eligibility::default(),
+            processing_times: ProcessingTimes::default(),
+            setup_times: SetupTimes::default(),
+            unavailability: Unavailability::default(),
+            frozen: Frozen::default(),
+            objective: Objective::default(),
+            resources: Resources::default(),
+            cumulative_resources: Vec::new(),
+            demands: Demands::default(),
+        }
+    }
+
+    /// Checks that the instance is consistent, so schedulers can run on it.
+    /// There has to be a processor, conflicts have to be between two different existing tasks,
+    /// resources have to be needed by existing tasks, cumulative resources have to exist
+    /// and be used by existing tasks within their capacities
+    /// and tasks have to take some time on every machine they're eligible for.
+    /// Eligibility, processing times and setup times have to refer to existing tasks and machines.
+    /// Blocked windows can't end before they start and frozen tasks have to be existing tasks
+    /// on existing eligible processors outside their blocked windows which neither overlap
+    /// on a machine nor overlap a conflicting task nor exceed the capacities together.
+    /// Minimised objectives also need an eligible machine for every task.
+    /// Returns the first inconsistency found.
+    pub fn validate(&self) -> Result<(), InstanceError> {
+        if self.processors == 0 {
+            return Err(InstanceError::NoProcessors);
+        }
+
+        for (first, second) in self.graph.iter() {
+            if second >= self.tasks.len() {
+                return Err(InstanceError::ConflictOutOfRange { first, second });
+            }
+            if first == second {
+                return Err(InstanceError::SelfConflict { task: first });
+            }
+        }
+
+        if let Some((task, resources)) = self
+            .resources
+            .resources
+            .iter()
+            .enumerate()
+            .skip(self.tasks.len())
+            .find(|(_, resources)| !resources.is_empty())
+        {
+            return Err(InstanceError::RequirementOutOfRange {
+                task,
+                resource: resources[0],
+            });
+        }
+
+        for (task, demands) in self.demands.demands.iter().enumerate() {
+            for &(resource, units) in demands {
+                let Some(cumulative) = self
+                    .cumulative_resources
+                    .get(resource)
+                    .filter(|_| task < self.tasks.len())
+                else {
+                    return Err(InstanceError::DemandOutOfRange { task, resource });
+                };
+                if units > cumulative.capacity {
+                    return Err(InstanceError::DemandExceedsCapacity { task, resource });
+                }
+            }
+        }
+
+        for (task, machines) in self.eligibility.machines.iter().enumerate() {
+            if let Some(&machine) = machines
+                .iter()
+                .find(|&&machine| task >= self.tasks.len() || machine >= self.processors)
+            {
+                return Err(InstanceError::EligibilityOutOfRange { task, machine });
+            }
+        }
+
+        for (task, times) in self.processing_times.times.iter().enumerate() {
+            if let Some(&machine) = times
+                .keys()
+                .find(|&&machine| task >= self.tasks.len() || machine >= self.processors)
+            {
+                return Err(InstanceError::ProcessingTimeOutOfRange { task, machine });
+            }
+        }
+
+        for (first, times) in self.setup_times.times.iter().enumerate() {
+            if let Some(&second) = times
+                .keys()
+                .find(|&&second| first >= self.tasks.len() || second >= self.tasks.len())
+            {
+                return Err(InstanceError::SetupTimeOutOfRange { first, second });
+            }
+        }
+
+        for task in 0..self.tasks.len() {
+            for machine in 0..self.processors {
+                if self.is_eligible(task, machine) && self.processing_time(task, machine) == 0 {
+                    return Err(InstanceError::ZeroProcessingTime { task, machine });
+                }
+            }
+            if self.objective.is_minimised() && self.min_processing_time(task).is_none() {
+                return Err(InstanceError::UnschedulableTask { task });
+            }
+        }
+
+        for (machine, windows) in self.unavailability.windows.iter().enumerate() {
+            if let Some(&(start, end)) = windows.iter().find(|&&(start, end)| start > end) {
+                return Err(InstanceError::InvalidBlockedWindow {
+                    machine,
+                    start,
+                    end,
+                });
+            }
+        }
+
+        self.validate_frozen()
+    }
+
+    /// Checks that frozen tasks exist, run on existing eligible processors outside their blocked
+    /// windows, don't overlap on a machine including setups, don't overlap conflicting frozen tasks
+    /// and don't exceed the capacities of cumulative resources together.
+    fn validate_frozen(&self) -> Result<(), InstanceError> {
+        let end_time = |task: usize, info: ScheduleInfo| {
+            info.start_time + self.processing_time(task, info.processor)
+        };
+
+        for (task, info) in self.frozen.iter() {
+            let machine = info.processor;
+            if task >= self.tasks.len() {
+                return Err(InstanceError::FrozenTaskOutOfRange { task });
+            }
+            if machine >= self.processors {
+                return Err(InstanceError::FrozenProcessorOutOfRange {
+                    task,
+                    processor: machine,
+                });
+            }
+            if !self.is_eligible(task, machine) {
+                return Err(InstanceError::FrozenIneligible { task, machine });
+            }
+            if !self
+                .unavailability
+                .is_available(machine, info.start_time, end_time(task, info))
+            {
+                return Err(InstanceError::FrozenBlocked { task, machine });
+            }
+        }
+
+        for machine in 0..self.processors {
+            // The previous task and the task ending the latest before the current one.
+            let mut previous: Option<(usize, u64)> = None;
+            let mut latest: Option<(usize, u64)> = None;
+            for &(start_time, task) in self.frozen.machine_tasks(machine) {
+                let overlapped = previous
+                    .filter(|&(previous_task, previous_end)| {
+                        start_time < previous_end + self.setup_time(Some(previous_task), task)
+                    })
+                    .or(latest.filter(|&(_, latest_end)| start_time < latest_end));
+                if let Some((first, _)) = overlapped {
+                    return Err(InstanceError::FrozenOverlap {
+                        machine,
+                        first,
+                        second: task,
+                    });
+                }
+
+                let end = start_time + self.processing_time(task, machine);
+                previous = Some((task, end));
+                if latest.iter().all(|&(_, latest_end)| latest_end < end) {
+                    latest = Some((task, end));
+                }
+            }
+        }
+
+        for (task, info) in self.frozen.iter() {
+            let end = end_time(task, info);
+            let conflict = self.conflicts(task).find(|&other| {
+                self.frozen.get(other).is_some_and(|other_info| {
+                    task < other
+                        && info.start_time < end_time(other, other_info)
+                        && other_info.start_time < end
+                })
+            });
+            if let Some(other) = conflict {
+                return Err(InstanceError::FrozenConflict {
+                    first: task,
+                    second: other,
+                });
+            }
+        }
+
+        for (resource, cumulative) in self.cumulative_resources.iter().enumerate() {
+            // Changes of the usage ordered by time with the releases before the uses.
+            let mut changes: Vec<(u64, bool, u64)> = Vec::new();
+            for (task, info) in self.frozen.iter() {
+                let units = self.demands.demand(task, resource);
+                if units > 0 {
+                    changes.push((info.start_time, true, units));
+                    changes.push((end_time(task, info), false, units));
+                }
+            }
+            changes.sort_unstable();
+
+            let mut usage = 0;
+            for (time, is_use, units) in changes {
+                if is_use {
+                    usage += units;
+                } else {
+                    usage -= units;
+                }
+                if usage > cumulative.capacity {
+                    return Err(InstanceError::FrozenCapacityExceeded { resource, time });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the given tasks conflict by an edge of the graph or a shared resource.
+    pub fn are_conflicted(&self, first: usize, second: usize) -> bool {
+        self.graph.are_conflicted(first, second)
+            || (first != second && self.resources.share(first, second))
+    }
+
+    /// Returns the tasks conflicting with the given task by an edge of the graph
+    /// or a shared resource. Conflicts are expanded lazily, so a task may be returned repeatedly.
+    pub fn conflicts(&self, task: usize) -> impl Iterator<Item = usize> + '_ {
+        let sharing = self
+            .resources
+            .resources(task)
+            .iter()
+            .flat_map(|&resource| self.resources.users(resource))
+            .copied()
+            .filter(move |&other| other != task);
+        self.graph.conflicts(task).iter().copied().chain(sharing)
+    }
+
+    /// Returns whether the given task uses more units of a cumulative resource than its capacity,
+    /// so it can't be scheduled at all.
+    pub fn exceeds_capacity(&self, task: usize) -> bool {
+        self.demands.demands(task).iter().any(|&(resource, units)| {
+            let capacity = self.cumulative_resources.get(resource);
+            units > capacity.map_or(0, |cumulative| cumulative.capacity)
+        })
+    }
+
+    /// Returns the earliest time the given task can start.
+    pub fn release_time(&self, task: usize) -> u64 {
+        self.tasks[task].release_time.unwrap_or_default()
+    }
+
+    /// Returns the time the given task has to be finished by.
+    /// It's the due date of the task limited by the deadline of the instance.
+    pub fn due_date(&self, task: usize) -> u64 {
+        self.tasks[task]
+            .due_date
+            .map_or(self.deadline, |due_date| due_date.min(self.deadline))
+    }
+
+    /// Returns the time the given task has to be finished by to be scheduled.
+    /// It's the due date of the task for the weight objective,
+    /// minimised objectives don't limit the completion time.
+    pub fn latest_completion(&self, task: usize) -> u64 {
+        if self.objective.is_minimised() {
+            u64::MAX
+        } else {
+            self.due_date(task)
+        }
+    }
+
+    /// Returns whether the given task can be processed on the given machine.
+    pub fn is_eligible(&self, task: usize, machine: usize) -> bool {
+        machine < self.processors && self.eligibility.is_eligible(task, machine)
+    }
+
+    /// Returns the processing time of the given task on the given machine.
+    pub fn processing_time(&self, task: usize, machine: usize) -> u64 {
+        self.processing_times
+            .get(task, machine)
+            .unwrap_or(self.tasks[task].processing_time)
+    }
+
+    /// Returns the shortest processing time of the given task over its eligible machines.
+    /// It returns None if the task can't be processed on any machine.
+    pub fn min_processing_time(&self, task: usize) -> Option<u64> {
+        (0..self.processors)
+            .filter(|&machine| self.is_eligible(task, machine))
+            .map(|machine| self.processing_time(task, machine))
+            .min()
+    }
+
+    /// Returns the setup time needed when the second task directly follows the first one on a machine.
+    /// There is no setup before the first task on a machine.
+    pub fn setup_time(&self, previous: Option<usize>, task: usize) -> u64 {
+        previous.map_or(0, |previous| self.setup_times.get(previous, task))
+    }
+
+    /// Returns whether all processors are identical, so every task has the same processing time on them.
+    pub fn has_identical_processors(&self) -> bool {
+        self.eligibility.is_empty() && self.processing_times.is_empty()
+    }
+
+    /// Returns whether the given task is frozen and can't be moved by schedulers.
+    pub fn is_frozen(&self, task: usize) -> bool {
+        self.frozen.get(task).is_some()
+    }
+
+    /// Returns the time the given machine is available for within the deadline.
+    /// Overlapping blocked windows only count once.
+    pub fn machine_capacity(&self, machine: usize) -> u64 {
+        let mut blocked = 0;
+        let mut blocked_until = 0;
+
+        for &(start, end) in self.unavailability.windows(machine) {
+            let end = end.min(self.deadline);
+            blocked += end.saturating_sub(start.max(blocked_until));
+            blocked_until = blocked_until.max(end);
+        }
+
+        self.deadline.saturating_sub(blocked)
+    }
+
+    /// Returns whether the given task can be processed on the given machine
+    /// and finished in time when started at the given time, see [`Instance::latest_completion`].
+    /// The machine has to be available for the whole processing time
+    /// and the task can't overlap tasks frozen on the machine.
+    pub fn fits(&self, task: usize, machine: usize, start_time: u64) -> bool {
+        let end_time = start_time + self.processing_time(task, machine);
+        self.is_eligible(task, machine)
+            && start_time >= self.release_time(task)
+            && end_time <= self.latest_completion(task)
+            && self
+                .unavailability
+                .is_available(machine, start_time, end_time)
+            && self.keeps_frozen(task, machine, start_time, end_time)
+    }
+
+    /// Returns whether the given task processed in the interval on the machine
+    /// doesn't overlap tasks frozen on the machine including setups around them.
+    fn keeps_frozen(&self, task: usize, machine: usize, start_time: u64, end_time: u64) -> bool {
+        self.frozen
+            .machine_tasks(machine)
+            .iter()
+            .all(|&(other_start_time, other)| {
+                let other_end_time = other_start_time + self.processing_time(other, machine);
+                other == task
+                    || end_time + self.setup_time(Some(task), other) <= other_start_time
+                    || other_end_time + self.setup_time(Some(other), task) <= start_time
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::read_test_file;
+
+    #[test]
+    fn test_validate() {
+        for file in std::fs::read_dir("src/test").expect("Error reading test directory") {
+            let path = file.expect("Error reading test file").path();
+            let instance = read_test_file(&path).expect("Error reading test file");
+            assert_eq!(instance.validate(), Ok(()), "{}", path.display());
+        }
+
+        let instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+
+        let mut invalid = instance.clone();
+        invalid.processors = 0;
+        assert_eq!(invalid.validate(), Err(InstanceError::NoProcessors));
+
+        let mut invalid = instance.clone();
+        invalid.graph = vec![Conflict(0, 1), Conflict(3, 11)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::ConflictOutOfRange {
+                first: 3,
+                second: 11
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.graph = vec![Conflict(4, 4)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::SelfConflict { task: 4 })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.tasks[2].processing_time = 0;
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::ZeroProcessingTime {
+                task: 2,
+                machine: 0
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.processing_times = vec![MachineTime(5, 1, 0)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::ZeroProcessingTime {
+                task: 5,
+                machine: 1
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.resources = [(2, 0), (11, 3)].into_iter().collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::RequirementOutOfRange {
+                task: 11,
+                resource: 3
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.cumulative_resources = vec![CumulativeResource::new("operators", 2)];
+        invalid.demands = [(3, 0, 2), (4, 1, 1)].into_iter().collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::DemandOutOfRange {
+                task: 4,
+                resource: 1
+            })
+        );
+        invalid.demands = [(3, 0, 2), (3, 0, 1)].into_iter().collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::DemandExceedsCapacity {
+                task: 3,
+                resource: 0
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.eligibility = vec![Assignment(2, 0), Assignment(11, 1)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::EligibilityOutOfRange {
+                task: 11,
+                machine: 1
+            })
+        );
+        invalid.eligibility = vec![Assignment(2, 3)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::EligibilityOutOfRange {
+                task: 2,
+                machine: 3
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.processing_times = vec![MachineTime(2, 3, 10)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::ProcessingTimeOutOfRange {
+                task: 2,
+                machine: 3
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.setup_times = vec![Setup(2, 11, 5)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::SetupTimeOutOfRange {
+                first: 2,
+                second: 11
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.unavailability = [(0, 10, 20), (1, 50, 40)].into_iter().collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::InvalidBlockedWindow {
+                machine: 1,
+                start: 50,
+                end: 40
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.frozen = [(2, ScheduleInfo::new(0, 0)), (11, ScheduleInfo::new(0, 1))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenTaskOutOfRange { task: 11 })
+        );
+        invalid.frozen = [(2, ScheduleInfo::new(0, 3))].into_iter().collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenProcessorOutOfRange {
+                task: 2,
+                processor: 3
+            })
+        );
+        invalid.frozen = [(2, ScheduleInfo::new(0, 0)), (3, ScheduleInfo::new(100, 0))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenOverlap {
+                machine: 0,
+                first: 2,
+                second: 3
+            })
+        );
+        invalid.frozen = [(2, ScheduleInfo::new(0, 0)), (3, ScheduleInfo::new(150, 0))]
+            .into_iter()
+            .collect();
+        assert_eq!(invalid.validate(), Ok(()));
+        invalid.setup_times = vec![Setup(2, 3, 20)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenOverlap {
+                machine: 0,
+                first: 2,
+                second: 3
+            })
+        );
+        invalid.frozen = [(0, ScheduleInfo::new(0, 0)), (1, ScheduleInfo::new(100, 1))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenConflict {
+                first: 0,
+                second: 1
+            })
+        );
+        invalid.frozen = [(0, ScheduleInfo::new(0, 0)), (1, ScheduleInfo::new(150, 1))]
+            .into_iter()
+            .collect();
+        assert_eq!(invalid.validate(), Ok(()));
+
+        let mut invalid = instance.clone();
+        invalid.frozen = [(2, ScheduleInfo::new(0, 0))].into_iter().collect();
+        invalid.eligibility = vec![Assignment(2, 1)].into();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenIneligible {
+                task: 2,
+                machine: 0
+            })
+        );
+        invalid.eligibility = Eligibility::default();
+        invalid.unavailability = [(0, 100, 120)].into_iter().collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenBlocked {
+                task: 2,
+                machine: 0
+            })
+        );
+
+        let mut invalid = instance.clone();
+        invalid.cumulative_resources = vec![CumulativeResource::new("operators", 2)];
+        invalid.demands = [(3, 0, 2), (8, 0, 1)].into_iter().collect();
+        invalid.frozen = [(3, ScheduleInfo::new(0, 0)), (8, ScheduleInfo::new(80, 1))]
+            .into_iter()
+            .collect();
+        assert_eq!(invalid.validate(), Ok(()));
+        invalid.frozen = [(3, ScheduleInfo::new(0, 0)), (8, ScheduleInfo::new(50, 1))]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::FrozenCapacityExceeded {
+                resource: 0,
+                time: 50
+            })
+        );
+
+        let mut invalid = instance;
+        invalid.eligibility = vec![Assignment(6, 3)].into();
+        invalid.objective = Objective::Makespan;
+        assert_eq!(
+            invalid.validate(),
+            Err(InstanceError::EligibilityOutOfRange {
+                task: 6,
+                machine: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_resources() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.graph = vec![Conflict(0, 1)].into();
+        instance.resources = [(2, 0), (3, 0), (4, 0), (4, 1), (5, 1), (2, 0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(instance.resources.resources(4), &[0, 1]);
+        assert_eq!(instance.resources.users(0), &[2, 3, 4]);
+        assert!(instance.are_conflicted(2, 3) && instance.are_conflicted(5, 4));
+        assert!(instance.are_conflicted(0, 1) && !instance.are_conflicted(3, 5));
+        assert!(!instance.are_conflicted(2, 2));
+
+        let mut conflicts: Vec<usize> = instance.conflicts(4).collect();
+        conflicts.sort_unstable();
+        assert_eq!(conflicts, vec![2, 3, 5]);
+
+        let text = crate::serialization::to_string(&instance).expect("Error serializing instance");
+        let read: Instance = crate::serialization::deserialize(&mut text.as_bytes())
+            .expect("Error deserializing instance");
+        assert_eq!(read.resources.users(0), &[2, 3, 4]);
+        assert_eq!(read.resources.resources(5), &[1]);
+
+        let json = serde_json::to_string(&instance).expect("Error serializing instance");
+        let read: Instance = serde_json::from_str(&json).expect("Error deserializing instance");
+        assert_eq!(read.resources.users(1), &[4, 5]);
+        assert!(read.are_conflicted(3, 4));
+    }
+
+    #[test]
+    fn test_demands() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.cumulative_resources = vec![
+            CumulativeResource::new("operators", 3),
+            CumulativeResource::new("crane", 1),
+        ];
+        instance.demands = [(4, 1, 1), (4, 0, 1), (2, 0, 2), (4, 0, 1), (7, 1, 0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(instance.demands.demands(4), &[(0, 2), (1, 1)]);
+        assert_eq!(instance.demands.demand(2, 0), 2);
+        assert_eq!(instance.demands.demand(2, 1), 0);
+        assert!(instance.demands.demands(7).is_empty());
+        assert_eq!(instance.validate(), Ok(()));
+
+        let text = crate::serialization::to_string(&instance).expect("Error serializing instance");
+        let read: Instance = crate::serialization::deserialize(&mut text.as_bytes())
+            .expect("Error deserializing instance");
+        assert_eq!(read.cumulative_resources, instance.cumulative_resources);
+        assert_eq!(read.demands.demands(4), &[(0, 2), (1, 1)]);
+
+        let json = serde_json::to_string(&instance).expect("Error serializing instance");
+        let read: Instance = serde_json::from_str(&json).expect("Error deserializing instance");
+        assert_eq!(read.cumulative_resources[1].name, "crane");
+        assert_eq!(read.demands.demand(2, 0), 2);
+    }
 }
@@ -0,0 +1,368 @@
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
+use crate::bounds;
+use crate::util::Machine;
+use crate::{list_algorithm, Instance, Observer, Progress, Schedule, ScheduleInfo, Scheduler};
+
+/// Default time limit of the branch and bound scheduler.
+const DEFAULT_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// A solution found by the exact scheduler.
+/// Contains the best found schedule and a proven upper bound on the optimal score.
+/// Minimised objectives are scheduled by the list algorithm without any bound.
+#[derive(Clone, Debug)]
+pub struct ExactSolution<'a> {
+    pub schedule: Schedule<'a>,
+    pub upper_bound: Option<u64>,
+}
+
+impl<'a> ExactSolution<'a> {
+    /// Returns whether the schedule is proven to be optimal.
+    /// Schedules of minimised objectives are never proven optimal.
+    pub fn is_optimal(&self) -> bool {
+        self.upper_bound
+            .is_some_and(|upper_bound| self.schedule.calculate_score() >= upper_bound)
+    }
+}
+
+/// Exact branch and bound scheduler.
+///
+/// Tasks are placed in order of non-decreasing start times, every task at the earliest time
+/// on a machine that doesn't conflict with already placed tasks. Every feasible schedule can be
+/// left-shifted into one generated this way, so exhausting the search proves optimality.
+/// Nodes are pruned with a fractional knapsack bound over the remaining machine capacity.
+/// Only the weight objective is optimised, other objectives are scheduled by the list algorithm.
+#[derive(Clone, Copy, Debug)]
+pub struct BranchAndBound {
+    time_limit: Duration,
+}
+
+impl BranchAndBound {
+    /// Creates a new scheduler that stops searching after the given time limit.
+    pub fn new(time_limit: Duration) -> Self {
+        Self { time_limit }
+    }
+
+    /// Returns whether the instance is scheduled by the list algorithm instead of the search,
+    /// which is the case for minimised objectives.
+    pub fn uses_list_algorithm(instance: &Instance) -> bool {
+        instance.objective.is_minimised()
+    }
+
+    /// Solves the instance. Returns the best found schedule and a proven upper bound.
+    pub fn solve(self, instance: &Instance) -> ExactSolution<'_> {
+        self.solve_observed(instance, &())
+    }
+
+    /// Solves the instance reporting improvements of the best schedule to the observer.
+    /// A cancelled search returns the best schedule found so far with a proven upper bound.
+    pub fn solve_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> ExactSolution<'a> {
+        if Self::uses_list_algorithm(instance) {
+            return ExactSolution {
+                schedule: list_algorithm(instance),
+                upper_bound: None,
+            };
+        }
+
+        let mut search = Search::new(instance, Instant::now() + self.time_limit, observer);
+        search.search(0);
+
+        ExactSolution {
+            upper_bound: Some(
+                search
+                    .best_score
+                    .max(search.open_bound)
+                    .min(bounds::upper_bound(instance)),
+            ),
+            schedule: search.best,
+        }
+    }
+}
+
+impl Default for BranchAndBound {
+    fn default() -> Self {
+        Self::new(DEFAULT_TIME_LIMIT)
+    }
+}
+
+impl Scheduler for BranchAndBound {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.solve(instance).schedule
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        self.solve_observed(instance, observer).schedule
+    }
+}
+
+/// Runs the branch and bound scheduler with the default time limit.
+pub fn branch_and_bound(instance: &Instance) -> Schedule<'_> {
+    BranchAndBound::default().schedule(instance)
+}
+
+/// A child of a search node. Contains the task placement and the bound of the child.
+#[derive(Clone, Copy, Debug)]
+struct Branch {
+    task: usize,
+    machine: usize,
+    start_time: u64,
+    bound: u64,
+}
+
+struct Search<'a, 'o> {
+    instance: &'a Instance,
+    observer: &'o dyn Observer,
+    time_limit: Instant,
+    timed_out: bool,
+    nodes: usize,
+    order: Vec<(usize, u64)>,
+    schedule: Schedule<'a>,
+    machines: Vec<Machine>,
+    score: u64,
+    best: Schedule<'a>,
+    best_score: u64,
+    open_bound: u64,
+}
+
+impl<'a, 'o> Search<'a, 'o> {
+    fn new(instance: &'a Instance, time_limit: Instant, observer: &'o dyn Observer) -> Self {
+        let mut order: Vec<(usize, u64)> = (0..instance.tasks.len())
+            .filter(|&task| !instance.is_frozen(task))
+            .filter_map(|task| instance.min_processing_time(task).map(|time| (task, time)))
+            .collect();
+        order.sort_unstable_by(|&(a, a_time), &(b, b_time)| {
+            (a_time as u128 * instance.tasks[b].weight as u128)
+                .cmp(&(b_time as u128 * instance.tasks[a].weight as u128))
+        });
+
+        let best = list_algorithm(instance);
+        let best_score = best.calculate_score();
+        let schedule = Schedule::new(instance);
+        let score = schedule.calculate_score();
+
+        Self {
+            instance,
+            observer,
+            time_limit,
+            timed_out: false,
+            nodes: 0,
+            order,
+            schedule,
+            machines: (0..instance.processors).map(Machine::new).collect(),
+            score,
+            best,
+            best_score,
+            open_bound: 0,
+        }
+    }
+
+    fn search(&mut self, last_start: u64) {
+        self.nodes += 1;
+
+        if self.score > self.best_score {
+            self.best_score = self.score;
+            self.best = self.schedule.clone();
+            self.observer.progress(Progress {
+                iteration: self.nodes,
+                best_score: self.best_score as i64,
+                improved: true,
+            });
+        }
+
+        let mut branches = self.branches(last_start);
+        branches.sort_unstable_by_key(|branch| Reverse(branch.bound));
+
+        for branch in branches {
+            if branch.bound <= self.best_score {
+                break;
+            }
+
+            if self.timed_out || Instant::now() >= self.time_limit || self.observer.is_cancelled() {
+                self.timed_out = true;
+                self.open_bound = self.open_bound.max(branch.bound);
+                break;
+            }
+
+            let machine = self.place(&branch);
+            self.search(branch.start_time);
+            self.unplace(&branch, machine);
+        }
+    }
+
+    fn branches(&mut self, last_start: u64) -> Vec<Branch> {
+        let mut branches = Vec::new();
+        let mut machines: Vec<Machine> = Vec::with_capacity(self.machines.len());
+        let symmetric = self.instance.has_identical_processors()
+            && self.instance.setup_times.is_empty()
+            && self.instance.unavailability.is_empty()
+            && self.instance.frozen.is_empty();
+
+        for &machine in &self.machines {
+            let time = machine.free_time.max(last_start);
+            if !symmetric
+                || machines
+                    .iter()
+                    .all(|other| other.free_time.max(last_start) != time)
+            {
+                machines.push(machine);
+            }
+        }
+
+        for task in 0..self.instance.tasks.len() {
+            if self.schedule.get_schedule(task).is_some() {
+                continue;
+            }
+
+            for machine in &machines {
+                let time = machine.available_time(self.instance, task).max(last_start);
+                if let Some(start_time) = self
+                    .schedule
+                    .calculate_non_conflict_time(task, machine.id, time)
+                {
+                    let mut branch = Branch {
+                        task,
+                        machine: machine.id,
+                        start_time,
+                        bound: 0,
+                    };
+
+                    let machine = self.place(&branch);
+                    branch.bound = self.score + self.remaining_bound(start_time);
+                    self.unplace(&branch, machine);
+
+                    branches.push(branch);
+                }
+            }
+        }
+
+        branches
+    }
+
+    /// Fractional knapsack bound for the tasks that can still be placed.
+    fn remaining_bound(&self, last_start: u64) -> u64 {
+        let deadline = self.instance.deadline;
+        let mut capacity: u64 = self
+            .machines
+            .iter()
+            .map(|machine| deadline.saturating_sub(machine.free_time.max(last_start)))
+            .sum();
+        let mut bound = 0;
+
+        for &(task, processing_time) in &self.order {
+            if self.schedule.get_schedule(task).is_some()
+                || last_start.max(self.instance.release_time(task)) + processing_time
+                    > self.instance.due_date(task)
+            {
+                continue;
+            }
+
+            let weight = self.instance.tasks[task].weight;
+
+            if processing_time <= capacity {
+                capacity -= processing_time;
+                bound += weight;
+            } else {
+                let fraction = weight as u128 * capacity as u128 / processing_time as u128;
+                bound += fraction as u64;
+                break;
+            }
+        }
+
+        bound
+    }
+
+    /// Places the task of the branch. Returns the previous state of its machine.
+    fn place(&mut self, branch: &Branch) -> Machine {
+        let info = ScheduleInfo::new(branch.start_time, branch.machine);
+
+        self.schedule.schedule(branch.task, info);
+        self.score += self.instance.tasks[branch.task].weight;
+
+        let machine = self.machines[branch.machine];
+        let free_time =
+            branch.start_time + self.instance.processing_time(branch.task, branch.machine);
+        self.machines[branch.machine].assign(branch.task, free_time);
+        machine
+    }
+
+    fn unplace(&mut self, branch: &Branch, machine: Machine) {
+        self.schedule.remove_schedule(branch.task);
+        self.score -= self.instance.tasks[branch.task].weight;
+        self.machines[branch.machine] = machine;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
+    use crate::{tresoldi, vns, ConflictGraph, Objective, SimulatedAnnealing, Task};
+
+    #[test]
+    fn test_branch_and_bound() {
+        run_test_files(BranchAndBound::new(Duration::from_millis(500)))
+            .expect("Error running tests")
+    }
+
+    #[test]
+    fn test_branch_and_bound_optimal() {
+        let instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        let solution = BranchAndBound::new(Duration::from_secs(60)).solve(&instance);
+        let score = solution.schedule.calculate_score();
+
+        assert!(solution.is_optimal());
+        assert_eq!(Some(score), solution.upper_bound);
+        assert!(score >= list_algorithm(&instance).calculate_score());
+        assert!(score >= vns(&instance).calculate_score());
+        assert!(score >= tresoldi(&instance).calculate_score());
+    }
+
+    #[test]
+    fn test_branch_and_bound_windows() {
+        let mut tasks = vec![Task::new(1, 3), Task::new(29, 9), Task::new(22, 6)];
+        tasks[0].release_time = Some(12);
+        let mut instance = Instance::new(2, 34, tasks, ConflictGraph::from_iter([(0, 1)]));
+        instance.unavailability = [(0, 8, 21)].into_iter().collect();
+        let solution = BranchAndBound::default().solve(&instance);
+
+        assert!(solution.is_optimal());
+        assert_eq!(solution.schedule.calculate_score(), 12);
+    }
+
+    #[test]
+    fn test_branch_and_bound_frozen() {
+        let instance =
+            read_test_file("src/test/test10_frozen.txt").expect("Error reading test file");
+        let solution = BranchAndBound::new(Duration::from_secs(60)).solve(&instance);
+        let annealing = SimulatedAnnealing {
+            seed: Some(1),
+            ..SimulatedAnnealing::default()
+        }
+        .schedule(&instance);
+
+        assert!(solution.schedule.verify());
+        assert!(solution.is_optimal());
+        assert!(solution.schedule.calculate_score() >= annealing.calculate_score());
+    }
+
+    #[test]
+    fn test_branch_and_bound_minimised() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.objective = Objective::Makespan;
+        let solution = BranchAndBound::default().solve(&instance);
+
+        assert!(BranchAndBound::uses_list_algorithm(&instance));
+        assert!(solution.schedule.verify());
+        assert_eq!(solution.upper_bound, None);
+        assert!(!solution.is_optimal());
+    }
+}
@@ -1,28 +1,36 @@
 use crate::util::{weighted_task_comparator, ScheduleBuilder, TaskWithId};
 use crate::{Instance, Schedule};
 
-pub fn list_algorithm(instance: &Instance) -> Schedule {
+pub fn list_algorithm(instance: &Instance) -> Schedule<'_> {
     let mut schedule = ScheduleBuilder::new(instance);
     let mut machines = schedule.new_machine_free_times();
 
-    let mut tasks: Vec<TaskWithId> = instance.tasks.iter().copied().enumerate().collect();
+    let mut tasks: Vec<TaskWithId> = instance
+        .tasks
+        .iter()
+        .copied()
+        .enumerate()
+        .filter(|&(id, _)| !instance.is_frozen(id))
+        .collect();
     tasks.sort_unstable_by(weighted_task_comparator);
 
-    for (id, task) in tasks {
+    for (id, _) in tasks {
+        let release_time = instance.release_time(id);
         let mut found_machine = None;
 
         for &machine in &machines {
-            if !schedule.in_conflict(id, machine.free_time) {
-                found_machine = Some(machine);
+            let time = machine.available_time(instance, id).max(release_time);
+            if instance.is_eligible(id, machine.id) && !schedule.in_conflict(id, time, machine.id) {
+                found_machine = Some((time, machine));
                 break;
             }
         }
 
-        if let Some(mut machine) = found_machine {
-            if machine.free_time + task.processing_time <= instance.deadline {
-                schedule.schedule(id, machine.free_time, machine.id);
+        if let Some((time, mut machine)) = found_machine {
+            if instance.fits(id, machine.id, time) {
+                schedule.schedule(id, time, machine.id);
                 machines.remove(&machine);
-                machine.free_time += task.processing_time;
+                machine.assign(id, time + instance.processing_time(id, machine.id));
                 machines.insert(machine);
                 continue;
             }
@@ -30,7 +38,11 @@ pub fn list_algorithm(instance: &Instance) -> Schedule {
 
         let mut machine_with_time = None;
         for &machine in &machines {
-            if let Some(time) = schedule.calculate_non_conflict_time(id, machine.free_time) {
+            if let Some(time) = schedule.calculate_non_conflict_time(
+                id,
+                machine.id,
+                machine.available_time(instance, id),
+            ) {
                 machine_with_time = Some((time, machine));
                 break;
             }
@@ -39,7 +51,7 @@ pub fn list_algorithm(instance: &Instance) -> Schedule {
         if let Some((time, mut machine)) = machine_with_time {
             schedule.schedule(id, time, machine.id);
             machines.remove(&machine);
-            machine.free_time = time + task.processing_time;
+            machine.assign(id, time + instance.processing_time(id, machine.id));
             machines.insert(machine);
         }
     }
@@ -50,10 +62,32 @@ pub fn list_algorithm(instance: &Instance) -> Schedule {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schedulers::test_utils::run_test_files;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
+    use crate::CumulativeResource;
 
     #[test]
     fn test_list_algorithm() {
         run_test_files(list_algorithm).expect("Error running tests")
     }
+
+    #[test]
+    fn test_list_algorithm_capacities() {
+        let mut instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        instance.cumulative_resources = vec![
+            CumulativeResource::new("operators", 2),
+            CumulativeResource::new("crane", 3),
+        ];
+        instance.demands = (0..instance.tasks.len())
+            .map(|task| (task, 0, 1))
+            .chain(
+                (0..instance.tasks.len())
+                    .step_by(4)
+                    .map(|task| (task, 1, 2)),
+            )
+            .collect();
+
+        let schedule = list_algorithm(&instance);
+        assert_eq!(schedule.violations(), vec![]);
+        assert!(schedule.calculate_score() > 0);
+    }
 }
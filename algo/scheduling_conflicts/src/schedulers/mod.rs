@@ -1,33 +1,61 @@
 use crate::{Instance, Schedule};
 
+mod branch_and_bound;
 mod list_algorithm;
+mod observer;
+mod pareto;
+mod portfolio;
+#[cfg(test)]
+mod properties;
+mod simulated_annealing;
+mod tabu_search;
 mod tresoldi;
 mod vns;
 
+pub use branch_and_bound::{branch_and_bound, BranchAndBound, ExactSolution};
 pub use list_algorithm::list_algorithm;
-pub use tresoldi::tresoldi;
-pub use vns::vns;
+pub use observer::{Observer, Progress};
+pub use pareto::{pareto_front, Pareto, ParetoPoint};
+pub use portfolio::{portfolio, Portfolio, Strategy};
+pub use simulated_annealing::{simulated_annealing, SimulatedAnnealing};
+pub use tabu_search::{tabu_search, TabuSearch};
+pub use tresoldi::{tresoldi, Restart, Tresoldi};
+pub use vns::{vns, Neighborhood, Vns};
 
 /// A scheduler. Schedules the tasks of an instance.
 pub trait Scheduler {
-    fn schedule(self, instance: &Instance) -> Schedule;
+    fn schedule(self, instance: &Instance) -> Schedule<'_>;
+
+    /// Schedules the tasks reporting the progress to the observer.
+    /// Schedulers without iterations ignore the observer.
+    fn schedule_observed<'a>(self, instance: &'a Instance, _observer: &dyn Observer) -> Schedule<'a>
+    where
+        Self: Sized,
+    {
+        self.schedule(instance)
+    }
 }
 
 impl<T: FnOnce(&Instance) -> Schedule> Scheduler for T {
-    fn schedule(self, instance: &Instance) -> Schedule {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
         self(instance)
     }
 }
 
 #[cfg(test)]
-mod test_utils {
+pub(crate) mod test_utils {
     use super::*;
     use std::error::Error;
+    use std::path::Path;
+
+    pub fn read_test_file<P: AsRef<Path>>(path: P) -> Result<Instance, Box<dyn Error>> {
+        let mut reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        Ok(crate::serialization::deserialize(&mut reader)?)
+    }
 
     pub fn run_test_files<T: Scheduler + Clone>(scheduler: T) -> Result<(), Box<dyn Error>> {
         for file in std::fs::read_dir("src/test")? {
-            let mut reader = std::io::BufReader::new(std::fs::File::open(file?.path())?);
-            let instance = crate::serialization::deserialize(&mut reader)?;
+            let instance = read_test_file(file?.path())?;
             if !scheduler.clone().schedule(&instance).verify() {
                 panic!("Invalid schedule created");
             }
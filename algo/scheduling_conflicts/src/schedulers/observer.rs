@@ -0,0 +1,141 @@
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Progress of a running scheduler.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Progress {
+    /// Number of finished iterations, e.g. shakes or explored search nodes.
+    pub iteration: usize,
+    /// Score of the best schedule found so far, see [`crate::Objective::score`].
+    pub best_score: i64,
+    /// Whether the last iteration improved the best schedule.
+    pub improved: bool,
+}
+
+/// Observes a running scheduler. Schedulers report their progress to it
+/// and stop with their best schedule found so far once it's cancelled.
+pub trait Observer: Sync {
+    /// Called when the scheduler makes progress.
+    fn progress(&self, _progress: Progress) {}
+
+    /// Returns whether the scheduler should stop.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// An observer that ignores the progress and never cancels.
+impl Observer for () {}
+
+/// Tracks the iterations and the best score of a scheduler, reports them to the observer
+/// and checks whether the scheduler should stop.
+pub(crate) struct Tracker<'o> {
+    observer: &'o dyn Observer,
+    deadline: Option<Instant>,
+    iteration: usize,
+    best_score: i64,
+}
+
+impl<'o> Tracker<'o> {
+    /// Creates a new tracker with an optional time limit in milliseconds.
+    pub fn new(observer: &'o dyn Observer, time_limit: Option<u64>) -> Self {
+        Self {
+            observer,
+            deadline: time_limit
+                .map(|time_limit| Instant::now() + Duration::from_millis(time_limit)),
+            iteration: 0,
+            best_score: i64::MIN,
+        }
+    }
+
+    /// Returns whether the time limit is reached or the observer cancelled the scheduler.
+    pub fn should_stop(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+            || self.observer.is_cancelled()
+    }
+
+    /// Records a finished iteration with the score of its schedule.
+    /// Returns whether the score improved the best score.
+    pub fn iteration(&mut self, score: i64) -> bool {
+        let improved = self.iteration == 0 || score > self.best_score;
+        self.iteration += 1;
+        self.best_score = self.best_score.max(score);
+        self.observer.progress(Progress {
+            iteration: self.iteration,
+            best_score: self.best_score,
+            improved,
+        });
+        improved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::read_test_file;
+    use crate::{BranchAndBound, Portfolio, Scheduler, Tresoldi, Vns};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Cancels the scheduler after the given number of progress reports.
+    struct CancelAfter {
+        limit: usize,
+        reports: AtomicUsize,
+    }
+
+    impl CancelAfter {
+        fn new(limit: usize) -> Self {
+            Self {
+                limit,
+                reports: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl Observer for CancelAfter {
+        fn progress(&self, progress: Progress) {
+            assert!(progress.iteration > 0);
+            self.reports.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.reports.load(Ordering::Relaxed) >= self.limit
+        }
+    }
+
+    #[test]
+    fn test_cancel() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+
+        let observer = CancelAfter::new(3);
+        let vns = Vns {
+            shakes: 1000,
+            ..Vns::default()
+        };
+        assert!(vns.schedule_observed(&instance, &observer).verify());
+        assert_eq!(observer.reports.load(Ordering::Relaxed), 3);
+
+        let observer = CancelAfter::new(2);
+        let tresoldi = Tresoldi {
+            iterations: 1000,
+            ..Tresoldi::default()
+        };
+        assert!(tresoldi.schedule_observed(&instance, &observer).verify());
+        assert_eq!(observer.reports.load(Ordering::Relaxed), 2);
+
+        let observer = CancelAfter::new(1);
+        let portfolio = Portfolio {
+            searches: Some(2),
+            ..Portfolio::default()
+        };
+        assert!(portfolio.schedule_observed(&instance, &observer).verify());
+
+        let observer = CancelAfter::new(0);
+        let solution = BranchAndBound::default().solve_observed(&instance, &observer);
+        assert!(solution.schedule.verify());
+        assert!(solution
+            .upper_bound
+            .is_some_and(|upper_bound| solution.schedule.calculate_score() <= upper_bound));
+    }
+}
@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use crate::schedulers::observer::Tracker;
+use crate::{Instance, Observer, Schedule, Scheduler, Vns};
+
+/// A schedule of the Pareto front with its criteria.
+#[derive(Clone, Debug, Serialize)]
+pub struct ParetoPoint<'a> {
+    #[serde(flatten)]
+    pub schedule: Schedule<'a>,
+    /// Weight of tasks finished by their due dates.
+    pub weight: u64,
+    /// Completion time of the last task.
+    pub makespan: u64,
+    /// Number of machines processing at least one task.
+    pub machines: usize,
+}
+
+impl<'a> ParetoPoint<'a> {
+    /// Evaluates the criteria of the schedule.
+    pub fn new(schedule: Schedule<'a>) -> Self {
+        let machines = (0..schedule.instance().processors)
+            .filter(|&machine| schedule.machine_tasks(machine).next().is_some())
+            .count();
+
+        Self {
+            weight: schedule.calculate_score(),
+            makespan: schedule.makespan(),
+            machines,
+            schedule,
+        }
+    }
+
+    /// Returns whether the point is at least as good as the other one in every criterion.
+    fn covers(&self, other: &ParetoPoint) -> bool {
+        self.weight >= other.weight
+            && self.makespan <= other.makespan
+            && self.machines <= other.machines
+    }
+}
+
+/// Forwards the cancellation of the front to a single search without reporting its progress.
+struct RunObserver<'t, 'o> {
+    tracker: &'t Tracker<'o>,
+}
+
+impl Observer for RunObserver<'_, '_> {
+    fn is_cancelled(&self) -> bool {
+        self.tracker.should_stop()
+    }
+}
+
+/// Multi-objective scheduler approximating the Pareto front of the weight of tasks on time,
+/// the makespan and the number of used machines.
+///
+/// Uses the epsilon-constraint method. For every number of machines and every makespan limit
+/// evenly spread up to the deadline, the variable neighborhood search maximises the weight
+/// of tasks on time using only the first machines and the limit as the deadline.
+/// The non-dominated schedules of all searches form the front.
+/// Minimised objectives schedule every task, so their searches minimise the objective
+/// of the instance for every number of machines the tasks can run on and ignore the limits.
+/// Every search reports one iteration with the best score found so far.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Pareto {
+    /// Number of makespan limits for every number of machines.
+    pub limits: usize,
+    /// Search run for every combination of the limits.
+    pub vns: Vns,
+    /// Wall-clock limit of all searches in milliseconds.
+    pub time_limit: Option<u64>,
+}
+
+impl Default for Pareto {
+    fn default() -> Self {
+        Self {
+            limits: 5,
+            vns: Vns::default(),
+            time_limit: None,
+        }
+    }
+}
+
+impl Pareto {
+    /// Returns the non-dominated schedules ordered by the number of machines and the makespan.
+    pub fn front(self, instance: &Instance) -> Vec<ParetoPoint<'_>> {
+        self.front_observed(instance, &())
+    }
+
+    /// Returns the non-dominated schedules reporting the progress to the observer.
+    /// A cancelled run returns the front of the finished searches.
+    pub fn front_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Vec<ParetoPoint<'a>> {
+        let mut tracker = Tracker::new(observer, self.time_limit);
+        let limits = if instance.objective.is_minimised() {
+            1
+        } else {
+            self.limits.max(1) as u64
+        };
+        let mut restricted = instance.clone();
+        let mut front: Vec<ParetoPoint> = Vec::new();
+
+        'search: for machines in (1..=instance.processors).rev() {
+            restricted.processors = machines;
+            if restricted.validate().is_err() {
+                continue;
+            }
+
+            for limit in (1..=limits).rev() {
+                if tracker.should_stop() {
+                    break 'search;
+                }
+
+                restricted.deadline = (instance.deadline * limit).div_ceil(limits);
+                let run = self
+                    .vns
+                    .clone()
+                    .schedule_observed(&restricted, &RunObserver { tracker: &tracker });
+
+                // The restrictions only tighten the instance, so the schedule stays valid.
+                let mut schedule = Schedule::new(instance);
+                for task in (0..instance.tasks.len()).filter(|&task| !instance.is_frozen(task)) {
+                    if let Some(&info) = run.get_schedule(task) {
+                        schedule.schedule(task, info);
+                    }
+                }
+
+                let point = ParetoPoint::new(schedule);
+                tracker.iteration(instance.objective.score(&point.schedule));
+                if front.iter().all(|other| !other.covers(&point)) {
+                    front.retain(|other| !point.covers(other));
+                    front.push(point);
+                }
+            }
+        }
+
+        front.sort_by_key(|point| (point.machines, point.makespan));
+        front
+    }
+}
+
+/// Approximates the Pareto front with the default parameters.
+pub fn pareto_front(instance: &Instance) -> Vec<ParetoPoint<'_>> {
+    Pareto::default().front(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::read_test_file;
+
+    #[test]
+    fn test_pareto_front() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let vns = Vns {
+            seed: Some(13),
+            shakes: 3,
+            ..Vns::default()
+        };
+        let front = Pareto {
+            limits: 3,
+            vns: vns.clone(),
+            ..Pareto::default()
+        }
+        .front(&instance);
+
+        assert!(!front.is_empty());
+        for (index, point) in front.iter().enumerate() {
+            assert_eq!(point.schedule.violations(), vec![]);
+            assert!(point.machines <= instance.processors);
+            for other in &front[index + 1..] {
+                assert!(!point.covers(other) && !other.covers(point));
+            }
+        }
+
+        let best_weight = front.iter().map(|point| point.weight).max();
+        assert_eq!(best_weight, Some(vns.schedule(&instance).calculate_score()));
+    }
+
+    #[test]
+    fn test_pareto_front_frozen() {
+        let instance =
+            read_test_file("src/test/test10_frozen.txt").expect("Error reading test file");
+        let front = Pareto {
+            limits: 2,
+            ..Pareto::default()
+        }
+        .front(&instance);
+
+        for point in front {
+            assert!(point.schedule.verify());
+        }
+    }
+}
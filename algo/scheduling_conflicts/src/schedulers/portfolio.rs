@@ -0,0 +1,255 @@
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Instance, Observer, Progress, Schedule, Scheduler, Tresoldi, Vns};
+
+/// A search run by the portfolio scheduler.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum Strategy {
+    Vns(Vns),
+    Tresoldi(Tresoldi),
+}
+
+impl Strategy {
+    fn with_seed(self, seed: u64) -> Self {
+        let seed = Some(seed);
+        match self {
+            Strategy::Vns(vns) => Strategy::Vns(Vns { seed, ..vns }),
+            Strategy::Tresoldi(tresoldi) => Strategy::Tresoldi(Tresoldi { seed, ..tresoldi }),
+        }
+    }
+
+    fn with_time_limit(self, time_limit: Option<u64>) -> Self {
+        match self {
+            Strategy::Vns(vns) => Strategy::Vns(Vns { time_limit, ..vns }),
+            Strategy::Tresoldi(tresoldi) => Strategy::Tresoldi(Tresoldi {
+                time_limit,
+                ..tresoldi
+            }),
+        }
+    }
+}
+
+impl Scheduler for Strategy {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        match self {
+            Strategy::Vns(vns) => vns.schedule_observed(instance, observer),
+            Strategy::Tresoldi(tresoldi) => tresoldi.schedule_observed(instance, observer),
+        }
+    }
+}
+
+/// Combines the progress of concurrent searches into the progress of the whole portfolio.
+struct SharedProgress<'o> {
+    observer: &'o dyn Observer,
+    iterations: AtomicUsize,
+    best_score: AtomicI64,
+}
+
+impl<'o> Observer for SharedProgress<'o> {
+    fn progress(&self, progress: Progress) {
+        let iteration = self.iterations.fetch_add(1, Ordering::Relaxed) + 1;
+        let previous = self
+            .best_score
+            .fetch_max(progress.best_score, Ordering::Relaxed);
+
+        self.observer.progress(Progress {
+            iteration,
+            best_score: previous.max(progress.best_score),
+            improved: iteration == 1 || progress.best_score > previous,
+        });
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.observer.is_cancelled()
+    }
+}
+
+/// Parallel multi-start scheduler.
+///
+/// Runs independently seeded searches on separate threads over the shared instance
+/// and returns the best valid schedule for the objective. The strategies are assigned to the searches in turns.
+/// Search `i` is seeded with `seed + i`, so runs with the same seed produce the same schedule
+/// unless a time limit of the strategies is reached. Without a seed the strategies keep their own.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Portfolio {
+    /// Number of searches. Defaults to the available parallelism.
+    pub searches: Option<usize>,
+    /// Base seed of the searches overriding the seeds of the strategies.
+    /// The seeds of the strategies are used if it's not given.
+    pub seed: Option<u64>,
+    /// Strategies of the searches.
+    pub strategies: Vec<Strategy>,
+}
+
+impl Default for Portfolio {
+    fn default() -> Self {
+        Self {
+            searches: None,
+            seed: None,
+            strategies: vec![
+                Strategy::Vns(Vns::default()),
+                Strategy::Tresoldi(Tresoldi::default()),
+            ],
+        }
+    }
+}
+
+impl Portfolio {
+    /// Sets the wall-clock limit in milliseconds of every strategy.
+    pub fn with_time_limit(self, time_limit: Option<u64>) -> Self {
+        Self {
+            strategies: self
+                .strategies
+                .into_iter()
+                .map(|strategy| strategy.with_time_limit(time_limit))
+                .collect(),
+            ..self
+        }
+    }
+}
+
+impl Scheduler for Portfolio {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        let observer = SharedProgress {
+            observer,
+            iterations: AtomicUsize::new(0),
+            best_score: AtomicI64::new(i64::MIN),
+        };
+        let observer = &observer;
+        let searches = self
+            .searches
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get));
+
+        let schedules: Vec<Schedule> = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .strategies
+                .iter()
+                .cycle()
+                .take(searches)
+                .enumerate()
+                .map(|(search, strategy)| {
+                    let strategy = match self.seed {
+                        Some(seed) => strategy.clone().with_seed(seed.wrapping_add(search as u64)),
+                        None => strategy.clone(),
+                    };
+                    scope.spawn(move || strategy.schedule_observed(instance, observer))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().ok())
+                .collect()
+        });
+
+        let objective = instance.objective;
+        let mut best: Option<Schedule> = None;
+
+        for schedule in schedules {
+            if schedule.verify()
+                && best
+                    .iter()
+                    .all(|best| objective.score(&schedule) > objective.score(best))
+            {
+                best = Some(schedule);
+            }
+        }
+
+        best.unwrap_or_else(|| Schedule::new(instance))
+    }
+}
+
+/// Runs the portfolio scheduler with the default parameters.
+pub fn portfolio(instance: &Instance) -> Schedule<'_> {
+    Portfolio::default().schedule(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
+
+    #[test]
+    fn test_portfolio() {
+        run_test_files(Portfolio {
+            searches: Some(4),
+            ..Portfolio::default()
+        })
+        .expect("Error running tests")
+    }
+
+    #[test]
+    fn test_portfolio_seeded() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let portfolio = Portfolio {
+            searches: Some(4),
+            seed: Some(3),
+            ..Portfolio::default()
+        };
+
+        let first = portfolio.clone().schedule(&instance);
+        let second = portfolio.schedule(&instance);
+        let single = Vns {
+            seed: Some(3),
+            ..Vns::default()
+        }
+        .schedule(&instance);
+
+        assert!(first.calculate_score() >= single.calculate_score());
+        for task in 0..instance.tasks.len() {
+            assert_eq!(first.get_schedule(task), second.get_schedule(task));
+        }
+    }
+
+    #[test]
+    fn test_portfolio_strategy_seeds() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let vns = Vns {
+            seed: Some(8),
+            ..Vns::default()
+        };
+        let portfolio = Portfolio {
+            searches: Some(2),
+            seed: None,
+            strategies: vec![Strategy::Vns(vns.clone())],
+        };
+
+        let schedule = portfolio.schedule(&instance);
+        let single = vns.schedule(&instance);
+
+        for task in 0..instance.tasks.len() {
+            assert_eq!(schedule.get_schedule(task), single.get_schedule(task));
+        }
+    }
+
+    #[test]
+    fn test_portfolio_time_limit() {
+        let portfolio = Portfolio::default().with_time_limit(Some(100));
+
+        assert!(portfolio.strategies.iter().all(|strategy| match strategy {
+            Strategy::Vns(vns) => vns.time_limit == Some(100),
+            Strategy::Tresoldi(tresoldi) => tresoldi.time_limit == Some(100),
+        }));
+    }
+}
@@ -0,0 +1,519 @@
+//! Property-based and differential tests of the schedulers on random instances.
+
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::bounds;
+use crate::generator::{ConflictModel, Distribution, Generator};
+use crate::{
+    list_algorithm, BranchAndBound, CumulativeResource, Eligibility, Instance, Objective, Pareto,
+    Portfolio, ProcessingTimes, Schedule, ScheduleInfo, Scheduler, SetupTimes, SimulatedAnnealing,
+    TabuSearch, Tresoldi, Vns,
+};
+
+/// Number of random instances every property is checked on.
+const CASES: u64 = 40;
+
+/// Number of tiny random instances the optimum is checked on. They're cheap to solve.
+const TINY_CASES: u64 = 1000;
+
+/// A scheduler under test with its name.
+type NamedScheduler = (&'static str, fn(&Instance) -> Schedule<'_>);
+
+/// Returns every scheduler with parameters small enough for many runs.
+fn schedulers() -> Vec<NamedScheduler> {
+    vec![
+        ("list", list_algorithm),
+        ("vns", |instance| {
+            Vns {
+                seed: Some(1),
+                shakes: 3,
+                ..Vns::default()
+            }
+            .schedule(instance)
+        }),
+        ("tresoldi", |instance| {
+            Tresoldi {
+                seed: Some(1),
+                iterations: 3,
+                ..Tresoldi::default()
+            }
+            .schedule(instance)
+        }),
+        ("branch_and_bound", |instance| {
+            BranchAndBound::new(Duration::from_millis(200)).schedule(instance)
+        }),
+        ("portfolio", |instance| {
+            Portfolio {
+                searches: Some(2),
+                seed: Some(1),
+                ..Portfolio::default()
+            }
+            .schedule(instance)
+        }),
+        ("tabu_search", |instance| {
+            TabuSearch {
+                iterations: 20,
+                ..TabuSearch::default()
+            }
+            .schedule(instance)
+        }),
+        ("simulated_annealing", |instance| {
+            SimulatedAnnealing {
+                seed: Some(1),
+                temperatures: 5,
+                moves: 50,
+                ..SimulatedAnnealing::default()
+            }
+            .schedule(instance)
+        }),
+    ]
+}
+
+/// Generates a random instance with up to the given numbers of tasks and processors.
+/// Some tasks get release times and due dates, some instances have a minimised objective,
+/// shared or cumulative resources, machine eligibility, machine dependent processing times,
+/// setup times, blocked windows or frozen tasks.
+fn random_instance(seed: u64, max_tasks: usize, max_processors: usize) -> Instance {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let conflicts = match rng.gen_range(0..3) {
+        0 => ConflictModel::ErdosRenyi {
+            density: rng.gen_range(0.0..0.6),
+        },
+        1 => ConflictModel::Interval {
+            length: rng.gen_range(0.0..0.5),
+        },
+        _ => ConflictModel::ClusteredCliques {
+            clusters: rng.gen_range(1..5),
+            density: rng.gen_range(0.0..0.2),
+        },
+    };
+
+    let mut instance = Generator {
+        tasks: rng.gen_range(1..=max_tasks),
+        processors: rng.gen_range(1..=max_processors),
+        tightness: rng.gen_range(0.2..1.5),
+        processing_time: Distribution::Uniform { min: 1, max: 30 },
+        weight: Distribution::Uniform { min: 1, max: 10 },
+        conflicts,
+        seed: Some(rng.gen()),
+    }
+    .generate();
+
+    let deadline = instance.deadline;
+    for task in &mut instance.tasks {
+        if rng.gen_bool(0.3) {
+            task.release_time = Some(rng.gen_range(0..=deadline / 2));
+        }
+        if rng.gen_bool(0.3) {
+            task.due_date = Some(rng.gen_range(1..=deadline));
+        }
+    }
+    if rng.gen_bool(0.25) {
+        instance.objective = match rng.gen_range(0..3) {
+            0 => Objective::Makespan,
+            1 => Objective::WeightedCompletion,
+            _ => Objective::WeightedTardiness,
+        };
+    }
+    if rng.gen_bool(0.3) {
+        let tasks = instance.tasks.len();
+        let resources = rng.gen_range(1..=3);
+        instance.resources = (0..rng.gen_range(0..=tasks))
+            .map(|_| (rng.gen_range(0..tasks), rng.gen_range(0..resources)))
+            .collect();
+    }
+    if rng.gen_bool(0.3) {
+        let tasks = instance.tasks.len();
+        instance.cumulative_resources = (0..rng.gen_range(1..=2))
+            .map(|resource| CumulativeResource::new(format!("r{resource}"), rng.gen_range(1..=4)))
+            .collect();
+        let capacities: Vec<u64> = instance
+            .cumulative_resources
+            .iter()
+            .map(|cumulative| cumulative.capacity)
+            .collect();
+        instance.demands = (0..tasks)
+            .flat_map(|task| (0..capacities.len()).map(move |resource| (task, resource)))
+            .filter_map(|(task, resource)| {
+                let units = rng.gen_range(1..=capacities[resource]);
+                rng.gen_bool(0.6).then_some((task, resource, units))
+            })
+            .collect();
+    }
+
+    let processors = instance.processors;
+    let tasks = instance.tasks.len();
+    if rng.gen_bool(0.3) {
+        let mut eligibility = Vec::new();
+        for task in 0..tasks {
+            if rng.gen_bool(0.5) {
+                let first = rng.gen_range(0..processors);
+                eligibility.push((task, first));
+                for machine in 0..processors {
+                    if machine != first && rng.gen_bool(0.5) {
+                        eligibility.push((task, machine));
+                    }
+                }
+            }
+        }
+        instance.eligibility = eligibility.into_iter().collect();
+    }
+    if rng.gen_bool(0.3) {
+        let mut times = Vec::new();
+        for task in 0..tasks {
+            for machine in 0..processors {
+                if rng.gen_bool(0.3) {
+                    times.push((task, machine, rng.gen_range(1..=30)));
+                }
+            }
+        }
+        instance.processing_times = times.into_iter().collect();
+    }
+    if rng.gen_bool(0.3) {
+        let mut setups = Vec::new();
+        for first in 0..tasks {
+            for second in 0..tasks {
+                if first != second && rng.gen_bool(0.2) {
+                    setups.push((first, second, rng.gen_range(1..=10)));
+                }
+            }
+        }
+        instance.setup_times = setups.into_iter().collect();
+    }
+    if rng.gen_bool(0.3) {
+        let mut windows = Vec::new();
+        for machine in 0..processors {
+            for _ in 0..rng.gen_range(0..=2) {
+                let start = rng.gen_range(0..=deadline);
+                windows.push((machine, start, start + rng.gen_range(1..=deadline / 5 + 1)));
+            }
+        }
+        instance.unavailability = windows.into_iter().collect();
+    }
+    if rng.gen_bool(0.3) {
+        let machines = instance.processors;
+        instance.frozen = random_frozen(&instance, machines, &mut rng)
+            .into_iter()
+            .collect();
+    }
+
+    instance
+}
+
+/// Generates a tiny instance of identical processors where only some machines
+/// have blocked windows and frozen tasks, so the machines aren't interchangeable.
+fn asymmetric_instance(seed: u64) -> Instance {
+    let mut instance = random_instance(seed, 6, 3);
+    let mut rng = StdRng::seed_from_u64(seed);
+    instance.objective = Objective::Weight;
+    instance.processors = rng.gen_range(2..=3);
+    instance.eligibility = Eligibility::default();
+    instance.processing_times = ProcessingTimes::default();
+    instance.setup_times = SetupTimes::default();
+
+    let deadline = instance.deadline;
+    let machines = rng.gen_range(1..instance.processors);
+    let mut windows = Vec::new();
+    for machine in 0..machines {
+        for _ in 0..rng.gen_range(1..=2) {
+            let start = rng.gen_range(0..=deadline);
+            windows.push((machine, start, start + rng.gen_range(1..=deadline / 3 + 1)));
+        }
+    }
+    instance.unavailability = windows.into_iter().collect();
+    instance.frozen = random_frozen(&instance, machines, &mut rng)
+        .into_iter()
+        .collect();
+
+    instance
+}
+
+/// Freezes some tasks of the instance on its first `machines` machines where
+/// the list algorithm could place them, so frozen tasks don't overlap on machines or with conflicting tasks
+/// and keep their release times, blocked windows and capacities.
+fn random_frozen(
+    instance: &Instance,
+    machines: usize,
+    rng: &mut StdRng,
+) -> Vec<(usize, ScheduleInfo)> {
+    let mut schedule = Schedule::new(instance);
+    let mut free = vec![(0, None); instance.processors];
+    let mut frozen = Vec::new();
+
+    for task in 0..instance.tasks.len() {
+        if !rng.gen_bool(0.3) {
+            continue;
+        }
+
+        let machine = rng.gen_range(0..machines);
+        let (free_time, last_task) = free[machine];
+        let minimum_time = free_time
+            + instance.setup_time(last_task, task)
+            + rng.gen_range(0..=instance.deadline / 4);
+        if let Some(start_time) = schedule.calculate_non_conflict_time(task, machine, minimum_time)
+        {
+            let info = ScheduleInfo::new(start_time, machine);
+            schedule.schedule(task, info);
+            free[machine] = (
+                start_time + instance.processing_time(task, machine),
+                Some(task),
+            );
+            frozen.push((task, info));
+        }
+    }
+
+    frozen
+}
+
+/// Placement of a task as its machine, start time and completion time.
+type Placement = (usize, u64, u64);
+
+/// Returns whether the task fits on the machine at the start time next to the placed tasks.
+/// Checks every constraint directly: eligibility, release time, due date, blocked windows,
+/// overlaps and setups on the machine, overlaps of conflicting tasks and capacities.
+fn fits(
+    instance: &Instance,
+    placed: &[Option<Placement>],
+    task: usize,
+    machine: usize,
+    start: u64,
+) -> bool {
+    let end = start + instance.processing_time(task, machine);
+    if !instance.is_eligible(task, machine)
+        || start < instance.release_time(task)
+        || end > instance.due_date(task)
+        || instance
+            .unavailability
+            .windows(machine)
+            .iter()
+            .any(|&(blocked_start, blocked_end)| blocked_start < end && start < blocked_end)
+    {
+        return false;
+    }
+
+    let others = || {
+        placed
+            .iter()
+            .enumerate()
+            .filter_map(|(other, placement)| placement.map(|placement| (other, placement)))
+    };
+    for (other, (other_machine, other_start, other_end)) in others() {
+        let overlaps = other_start < end && start < other_end;
+        if overlaps && (other_machine == machine || instance.are_conflicted(task, other)) {
+            return false;
+        }
+    }
+
+    let previous = others()
+        .filter(|&(_, (other_machine, other_start, _))| {
+            other_machine == machine && other_start < start
+        })
+        .max_by_key(|&(_, (_, other_start, _))| other_start);
+    if let Some((previous, (_, _, previous_end))) = previous {
+        if previous_end + instance.setup_time(Some(previous), task) > start {
+            return false;
+        }
+    }
+    let next = others()
+        .filter(|&(_, (other_machine, other_start, _))| {
+            other_machine == machine && other_start >= start
+        })
+        .min_by_key(|&(_, (_, other_start, _))| other_start);
+    if let Some((next, (_, next_start, _))) = next {
+        if end + instance.setup_time(Some(task), next) > next_start {
+            return false;
+        }
+    }
+
+    instance
+        .cumulative_resources
+        .iter()
+        .enumerate()
+        .all(|(resource, cumulative)| {
+            let units = instance.demands.demand(task, resource);
+            units == 0
+                || std::iter::once(start)
+                    .chain(others().map(|(_, (_, other_start, _))| other_start))
+                    .filter(|&time| start <= time && time < end)
+                    .all(|time| {
+                        let used: u64 = others()
+                            .filter(|&(_, (_, other_start, other_end))| {
+                                other_start <= time && time < other_end
+                            })
+                            .map(|(other, _)| instance.demands.demand(other, resource))
+                            .sum();
+                        used + units <= cumulative.capacity
+                    })
+        })
+}
+
+/// Returns the optimal score of the instance by enumerating schedules of the tasks
+/// placed in order of their start times.
+/// A task can only start at its release time, at the end of a blocked window of its machine
+/// or at the completion of a placed task, with or without the setup after it.
+/// Every schedule can be left-shifted until each task starts at such a time,
+/// so the best of them is optimal.
+fn brute_force_optimum(instance: &Instance) -> u64 {
+    fn search(
+        instance: &Instance,
+        placed: &mut [Option<Placement>],
+        last_start: u64,
+        score: u64,
+        best: &mut u64,
+    ) {
+        *best = (*best).max(score);
+        let remaining: u64 = (0..instance.tasks.len())
+            .filter(|&task| placed[task].is_none())
+            .map(|task| instance.tasks[task].weight)
+            .sum();
+        if score + remaining <= *best {
+            return;
+        }
+
+        for task in 0..instance.tasks.len() {
+            if placed[task].is_some() {
+                continue;
+            }
+
+            for machine in 0..instance.processors {
+                let mut starts: Vec<u64> = placed
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(other, placement)| placement.map(|placement| (other, placement)))
+                    .flat_map(|(other, (_, _, other_end))| {
+                        [
+                            other_end,
+                            other_end + instance.setup_time(Some(other), task),
+                        ]
+                    })
+                    .chain(
+                        instance
+                            .unavailability
+                            .windows(machine)
+                            .iter()
+                            .map(|&(_, end)| end),
+                    )
+                    .chain([instance.release_time(task)])
+                    .filter(|&start| start >= last_start)
+                    .collect();
+                starts.sort_unstable();
+                starts.dedup();
+
+                for start in starts {
+                    if fits(instance, placed, task, machine, start) {
+                        let end = start + instance.processing_time(task, machine);
+                        placed[task] = Some((machine, start, end));
+                        let weight = instance.tasks[task].weight;
+                        search(instance, placed, start, score + weight, best);
+                        placed[task] = None;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut placed: Vec<Option<Placement>> = (0..instance.tasks.len())
+        .map(|task| {
+            instance.frozen.get(task).map(|info| {
+                let end = info.start_time + instance.processing_time(task, info.processor);
+                (info.processor, info.start_time, end)
+            })
+        })
+        .collect();
+    let frozen_score = instance
+        .frozen
+        .iter()
+        .filter(|&(task, _)| placed[task].is_some_and(|(_, _, end)| end <= instance.due_date(task)))
+        .map(|(task, _)| instance.tasks[task].weight)
+        .sum();
+
+    let mut best = 0;
+    search(instance, &mut placed, 0, frozen_score, &mut best);
+    best
+}
+
+#[test]
+fn test_random_instances() {
+    for seed in 0..CASES {
+        let instance = random_instance(seed, 30, 4);
+        assert_eq!(instance.validate(), Ok(()), "instance {seed}");
+        let upper_bound = bounds::upper_bound(&instance);
+
+        for (name, scheduler) in schedulers() {
+            let schedule = scheduler(&instance);
+
+            assert_eq!(schedule.violations(), vec![], "{name} on instance {seed}");
+            assert!(
+                schedule.calculate_score() <= upper_bound,
+                "{name} exceeds the bound on instance {seed}"
+            );
+        }
+
+        let front = Pareto {
+            limits: 2,
+            vns: Vns {
+                seed: Some(1),
+                shakes: 2,
+                ..Vns::default()
+            },
+            ..Pareto::default()
+        }
+        .front(&instance);
+        for point in front {
+            assert_eq!(
+                point.schedule.violations(),
+                vec![],
+                "front of instance {seed}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_tiny_instances_optimum() {
+    for seed in 0..TINY_CASES {
+        let mut instance = random_instance(seed, 6, 2);
+        instance.objective = Objective::Weight;
+        let optimum = brute_force_optimum(&instance);
+
+        assert!(optimum <= bounds::upper_bound(&instance), "instance {seed}");
+        for (name, scheduler) in schedulers() {
+            let schedule = scheduler(&instance);
+
+            assert!(schedule.verify(), "{name} on instance {seed}");
+            assert!(
+                schedule.calculate_score() <= optimum,
+                "{name} beats the optimum on instance {seed}"
+            );
+        }
+
+        let solution = BranchAndBound::default().solve(&instance);
+        assert!(solution.is_optimal(), "instance {seed}");
+        assert_eq!(
+            solution.schedule.calculate_score(),
+            optimum,
+            "instance {seed}"
+        );
+    }
+}
+
+#[test]
+fn test_asymmetric_machines_optimum() {
+    for seed in 0..TINY_CASES {
+        let instance = asymmetric_instance(seed);
+        assert_eq!(instance.validate(), Ok(()), "instance {seed}");
+        let optimum = brute_force_optimum(&instance);
+
+        let solution = BranchAndBound::default().solve(&instance);
+        assert!(solution.schedule.verify(), "instance {seed}");
+        assert!(solution.is_optimal(), "instance {seed}");
+        assert_eq!(
+            solution.schedule.calculate_score(),
+            optimum,
+            "instance {seed}"
+        );
+    }
+}
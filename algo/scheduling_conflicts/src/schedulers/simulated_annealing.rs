@@ -0,0 +1,157 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+use crate::schedulers::observer::Tracker;
+use crate::schedulers::vns::init;
+use crate::{Instance, Neighborhood, Observer, Schedule, Scheduler};
+
+/// Simulated annealing scheduler.
+///
+/// Starts from a list schedule and moves to random schedules of the neighborhoods.
+/// Worse schedules are accepted with probability `exp(-loss / temperature)`
+/// and the temperature decreases geometrically after every temperature step.
+/// Runs with the same seed produce the same schedule unless the time limit is reached
+/// or the run is cancelled.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SimulatedAnnealing {
+    /// Seed of the random generator. A random seed is used if it's not given.
+    pub seed: Option<u64>,
+    /// Initial temperature. Defaults to the average weight of the tasks.
+    /// Scores of minimised objectives usually need a higher temperature.
+    pub initial_temperature: Option<f64>,
+    /// Factor the temperature is multiplied by after every temperature step.
+    pub cooling: f64,
+    /// Number of temperature steps.
+    pub temperatures: usize,
+    /// Number of moves tried at every temperature.
+    pub moves: usize,
+    /// Wall-clock limit of the search in milliseconds.
+    pub time_limit: Option<u64>,
+    /// Neighborhoods the random moves are drawn from.
+    pub neighborhoods: Vec<Neighborhood>,
+}
+
+impl Default for SimulatedAnnealing {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            initial_temperature: None,
+            cooling: 0.95,
+            temperatures: 100,
+            moves: 500,
+            time_limit: None,
+            neighborhoods: Neighborhood::ALL.to_vec(),
+        }
+    }
+}
+
+impl Scheduler for SimulatedAnnealing {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        let mut tracker = Tracker::new(observer, self.time_limit);
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut current = init::schedule(instance);
+        let mut current_score = current.objective_score();
+        let mut best = current.clone();
+        let mut best_score = current_score;
+        tracker.iteration(best_score);
+
+        let mut temperature = self.initial_temperature.unwrap_or_else(|| {
+            let weight: u64 = instance.tasks.iter().map(|task| task.weight).sum();
+            weight as f64 / instance.tasks.len().max(1) as f64
+        });
+
+        for _ in 0..self.temperatures {
+            if tracker.should_stop() {
+                break;
+            }
+
+            for _ in 0..self.moves {
+                let Some(&neighborhood) = self.neighborhoods.choose(&mut rng) else {
+                    break;
+                };
+                let Some(step) = neighborhood.random(&current, &mut rng) else {
+                    continue;
+                };
+
+                let score = current.trial(
+                    |schedule| step.apply(schedule),
+                    |schedule| schedule.objective_score(),
+                );
+                let loss = (current_score - score).max(0) as f64;
+
+                if loss == 0.0 || rng.gen::<f64>() < (-loss / temperature).exp() {
+                    step.apply(&mut current);
+                    current_score = score;
+
+                    if current_score > best_score {
+                        best_score = current_score;
+                        best = current.clone();
+                    }
+                }
+            }
+
+            tracker.iteration(best_score);
+            temperature *= self.cooling;
+        }
+
+        best.into()
+    }
+}
+
+/// Runs the simulated annealing with the default parameters.
+pub fn simulated_annealing(instance: &Instance) -> Schedule<'_> {
+    SimulatedAnnealing::default().schedule(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
+
+    #[test]
+    fn test_simulated_annealing() {
+        run_test_files(SimulatedAnnealing {
+            temperatures: 10,
+            moves: 100,
+            ..SimulatedAnnealing::default()
+        })
+        .expect("Error running tests")
+    }
+
+    #[test]
+    fn test_simulated_annealing_seeded() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let annealing = SimulatedAnnealing {
+            seed: Some(11),
+            temperatures: 10,
+            moves: 100,
+            ..SimulatedAnnealing::default()
+        };
+
+        let first = annealing.clone().schedule(&instance);
+        let second = annealing.schedule(&instance);
+
+        assert!(first.verify());
+        assert!(
+            first.calculate_score() >= Schedule::from(init::schedule(&instance)).calculate_score()
+        );
+        for task in 0..instance.tasks.len() {
+            assert_eq!(first.get_schedule(task), second.get_schedule(task));
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::VecDeque;
+
+use ahash::{HashSet, HashSetExt};
+use serde::{Deserialize, Serialize};
+
+use crate::schedulers::observer::Tracker;
+use crate::schedulers::vns::{init, Step};
+use crate::{Instance, Neighborhood, Observer, Schedule, Scheduler};
+
+/// Tabu search scheduler.
+///
+/// Starts from a list schedule and in every iteration moves to the best schedule
+/// of the neighborhoods, even if it's worse than the current one. Recently visited schedules
+/// are tabu unless they improve the best found schedule.
+/// The search is deterministic unless the time limit is reached or the run is cancelled.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TabuSearch {
+    /// Number of iterations.
+    pub iterations: usize,
+    /// Number of iterations a visited schedule stays tabu.
+    pub tenure: usize,
+    /// Wall-clock limit of the search in milliseconds.
+    pub time_limit: Option<u64>,
+    /// Neighborhoods searched in every iteration.
+    pub neighborhoods: Vec<Neighborhood>,
+}
+
+impl Default for TabuSearch {
+    fn default() -> Self {
+        Self {
+            iterations: 200,
+            tenure: 20,
+            time_limit: None,
+            neighborhoods: Neighborhood::ALL.to_vec(),
+        }
+    }
+}
+
+impl Scheduler for TabuSearch {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        let mut tracker = Tracker::new(observer, self.time_limit);
+
+        let mut current = init::schedule(instance);
+        let mut best = current.clone();
+        let mut best_score = best.objective_score();
+        tracker.iteration(best_score);
+
+        let mut tabu = HashSet::new();
+        let mut tabu_order = VecDeque::with_capacity(self.tenure + 1);
+        tabu.insert(current.fingerprint());
+        tabu_order.push_back(current.fingerprint());
+
+        for _ in 0..self.iterations {
+            if tracker.should_stop() {
+                break;
+            }
+
+            let mut candidate = None;
+            let mut candidate_score = 0;
+
+            for &neighborhood in &self.neighborhoods {
+                let moves: Vec<Step> = neighborhood.search(&current).collect();
+                for step in moves {
+                    let (score, fingerprint) = current.trial(
+                        |schedule| step.apply(schedule),
+                        |schedule| (schedule.objective_score(), schedule.fingerprint()),
+                    );
+                    if (candidate.is_none() || score > candidate_score)
+                        && (score > best_score || !tabu.contains(&fingerprint))
+                    {
+                        candidate_score = score;
+                        candidate = Some((step, fingerprint));
+                    }
+                }
+            }
+
+            let Some((step, fingerprint)) = candidate else {
+                break;
+            };
+
+            if tabu.insert(fingerprint) {
+                tabu_order.push_back(fingerprint);
+            }
+            while tabu_order.len() > self.tenure {
+                if let Some(fingerprint) = tabu_order.pop_front() {
+                    tabu.remove(&fingerprint);
+                }
+            }
+
+            step.apply(&mut current);
+
+            if tracker.iteration(candidate_score) && candidate_score > best_score {
+                best_score = candidate_score;
+                best = current.clone();
+            }
+        }
+
+        best.into()
+    }
+}
+
+/// Runs the tabu search with the default parameters.
+pub fn tabu_search(instance: &Instance) -> Schedule<'_> {
+    TabuSearch::default().schedule(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
+
+    #[test]
+    fn test_tabu_search() {
+        run_test_files(TabuSearch {
+            iterations: 10,
+            ..TabuSearch::default()
+        })
+        .expect("Error running tests")
+    }
+
+    #[test]
+    fn test_tabu_search_improves() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let schedule = tabu_search(&instance);
+
+        assert!(schedule.verify());
+        assert!(
+            schedule.calculate_score()
+                >= Schedule::from(init::schedule(&instance)).calculate_score()
+        );
+    }
+}
@@ -1,17 +1,84 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use ahash::HashMap;
-use rand::prelude::{SliceRandom, ThreadRng};
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
+use crate::schedulers::observer::Tracker;
 use crate::util::TaskWithId;
-use crate::{Instance, Schedule, ScheduleInfo};
+use crate::{list_algorithm, Instance, Observer, Schedule, ScheduleInfo, Scheduler};
+
+/// Marks intervals of a timeline in which the machine is unavailable.
+const BLOCKED: usize = usize::MAX;
+
+/// A scheduled task with its start time.
+type Placement = (usize, u64);
+
+/// Occupied intervals of a machine. Maps start times and owners of the intervals,
+/// which are task ids or [`BLOCKED`], to their end times.
+/// Intervals may overlap, e.g. overlapping blocked windows. Only intervals starting at most
+/// the longest interval before a time can cover it, so overlaps are found by a range query.
+#[derive(Clone, Debug, Default)]
+struct Timeline {
+    intervals: BTreeMap<(u64, usize), u64>,
+    longest: u64,
+}
+
+impl Timeline {
+    /// Marks the `[start, end)` interval as occupied by the owner.
+    fn occupy(&mut self, start: u64, end: u64, owner: usize) {
+        if start < end {
+            self.intervals.insert((start, owner), end);
+            self.longest = self.longest.max(end - start);
+        }
+    }
+
+    /// Frees the interval started at the given time if it's occupied by the owner.
+    fn free(&mut self, start: u64, owner: usize) {
+        self.intervals.remove(&(start, owner));
+    }
+
+    /// Returns whether no interval overlaps `[start, end)`.
+    fn is_free(&self, start: u64, end: u64) -> bool {
+        start >= end
+            || self
+                .intervals
+                .range((start.saturating_sub(self.longest), 0)..(end, 0))
+                .all(|(_, &other_end)| other_end <= start)
+    }
+
+    /// Returns the free gaps of the timeline within `[from, to)` in order.
+    fn gaps(&self, from: u64, to: u64) -> Vec<(u64, u64)> {
+        let mut gaps = Vec::new();
+        let mut cursor = from;
+
+        for (&(start, _), &end) in &self.intervals {
+            if cursor >= to {
+                break;
+            }
+
+            if start > cursor {
+                gaps.push((cursor, start.min(to)));
+            }
 
-const ITERATIONS: usize = 10;
+            cursor = cursor.max(end);
+        }
+
+        if cursor < to {
+            gaps.push((cursor, to));
+        }
+
+        gaps
+    }
+}
 
 struct ScheduleBuilder<'a> {
     instance: &'a Instance,
     score: u64,
     tasks: Vec<TaskWithId>,
-    matrix: Vec<Vec<Option<usize>>>,
+    timelines: Vec<Timeline>,
     scheduled: HashMap<usize, (usize, u64)>,
 }
 
@@ -21,43 +88,105 @@ impl<'a> ScheduleBuilder<'a> {
             instance,
             score: Default::default(),
             tasks: Default::default(),
-            matrix: Default::default(),
+            timelines: Default::default(),
             scheduled: Default::default(),
         }
     }
 
-    fn random(instance: &'a Instance, rng: &mut ThreadRng) -> Self {
-        let mut tasks: Vec<TaskWithId> = instance.tasks.clone().into_iter().enumerate().collect();
+    fn random(instance: &'a Instance, rng: &mut StdRng) -> Self {
+        let mut tasks: Vec<TaskWithId> = instance
+            .tasks
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(id, _)| !instance.is_frozen(id))
+            .collect();
         tasks.shuffle(rng);
+        Self::with_order(instance, tasks)
+    }
+
+    fn perturbed(&self, swaps: usize, rng: &mut StdRng) -> Self {
+        let mut tasks = self.tasks.clone();
+        for _ in 0..swaps {
+            let first = rng.gen_range(0..tasks.len());
+            let second = rng.gen_range(0..tasks.len());
+            tasks.swap(first, second);
+        }
+        Self::with_order(self.instance, tasks)
+    }
+
+    fn with_order(instance: &'a Instance, tasks: Vec<TaskWithId>) -> Self {
+        let deadline = instance.deadline;
+        let mut timelines = vec![Timeline::default(); instance.processors];
+
+        for (machine, timeline) in timelines.iter_mut().enumerate() {
+            for &(start, end) in instance.unavailability.windows(machine) {
+                timeline.occupy(start.min(deadline), end.min(deadline), BLOCKED);
+            }
+        }
+
+        let mut scheduled = HashMap::default();
+        for (task, info) in instance.frozen.iter() {
+            let end = info.start_time + instance.processing_time(task, info.processor);
+            timelines[info.processor].occupy(
+                info.start_time.min(deadline),
+                end.min(deadline),
+                task,
+            );
+            scheduled.insert(task, (info.processor, info.start_time));
+        }
+
         Self {
             instance,
             score: 0,
             tasks,
-            matrix: vec![vec![None; instance.processors]; instance.deadline as usize],
-            scheduled: Default::default(),
+            timelines,
+            scheduled,
         }
     }
 
+    /// Inserts unscheduled tasks at the earliest possible times in the task order.
+    /// Only times a task can become placeable at are visited: the release times,
+    /// the ends of blocked windows and scheduled tasks and the ends of their setups.
     fn greedy_insert(&mut self) -> bool {
         let mut change = false;
 
-        for time in 0..self.matrix.len() as u64 {
-            for machine in 0..self.matrix[0].len() {
-                if self.matrix[time as usize][machine].is_none() {
-                    for task in &self.tasks {
-                        if !self.scheduled.contains_key(&task.0)
-                            && self.check_time(time, machine, task)
-                            && self.check_conflicts(task, time)
-                        {
-                            for instant in time..(time + task.1.processing_time) {
-                                self.matrix[instant as usize][machine] = Some(task.0);
-                            }
-                            self.score += task.1.weight;
-                            self.scheduled.insert(task.0, (machine, time));
-                            change = true;
-                            break;
-                        }
-                    }
+        let mut times: BTreeSet<u64> = std::iter::once(0)
+            .chain((0..self.instance.tasks.len()).map(|task| self.instance.release_time(task)))
+            .chain(
+                (0..self.instance.processors)
+                    .flat_map(|machine| self.instance.unavailability.windows(machine))
+                    .map(|&(_, end)| end),
+            )
+            .collect();
+        for (&task, &(machine, time)) in &self.scheduled {
+            times.extend(self.free_times(task, machine, time));
+        }
+
+        while let Some(time) = times.pop_first() {
+            if time >= self.instance.deadline {
+                break;
+            }
+
+            for machine in 0..self.timelines.len() {
+                if !self.timelines[machine].is_free(time, time + 1) {
+                    continue;
+                }
+
+                let task = self.tasks.iter().copied().find(|task| {
+                    !self.scheduled.contains_key(&task.0)
+                        && self.check_time(time, machine, task)
+                        && self.check_conflicts(task, time, machine)
+                        && self.check_setups(task.0, time, machine, task.0)
+                });
+
+                if let Some(task) = task {
+                    let end_time = time + self.instance.processing_time(task.0, machine);
+                    self.timelines[machine].occupy(time, end_time, task.0);
+                    self.score += task.1.weight;
+                    self.scheduled.insert(task.0, (machine, time));
+                    times.extend(self.free_times(task.0, machine, time));
+                    change = true;
                 }
             }
         }
@@ -65,6 +194,22 @@ impl<'a> ScheduleBuilder<'a> {
         change
     }
 
+    /// Returns the times other tasks can start at after the given scheduled task
+    /// regarding the setups after it.
+    fn free_times(&self, task: usize, machine: usize, time: u64) -> Vec<u64> {
+        let end_time = time + self.instance.processing_time(task, machine);
+        let mut times = vec![end_time];
+
+        if !self.instance.setup_times.is_empty() {
+            times.extend(
+                (0..self.instance.tasks.len())
+                    .map(|other| end_time + self.instance.setup_time(Some(task), other)),
+            );
+        }
+
+        times
+    }
+
     fn local_search(&mut self) -> bool {
         let mut change = false;
 
@@ -75,19 +220,18 @@ impl<'a> ScheduleBuilder<'a> {
                         continue;
                     }
 
+                    let old_time = self.instance.processing_time(old.0, machine);
+                    let new_time = self.instance.processing_time(task.0, machine);
+
                     if (task.1.weight > old.1.weight
-                        || (task.1.weight == old.1.weight
-                            && task.1.processing_time < old.1.processing_time))
+                        || (task.1.weight == old.1.weight && new_time < old_time))
+                        && self.instance.fits(task.0, machine, time)
                         && self.check_hole(&old, &task)
-                        && self.check_conflicts(&task, time)
+                        && self.check_conflicts(&task, time, machine)
+                        && self.check_setups(task.0, time, machine, old.0)
                     {
-                        for instant in time..(time + old.1.processing_time) {
-                            self.matrix[instant as usize][machine] = None;
-                        }
-
-                        for instant in time..(time + task.1.processing_time) {
-                            self.matrix[instant as usize][machine] = Some(task.0);
-                        }
+                        self.timelines[machine].free(time, old.0);
+                        self.timelines[machine].occupy(time, time + new_time, task.0);
 
                         self.score = self.score - old.1.weight + task.1.weight;
                         self.scheduled.remove(&old.0);
@@ -102,41 +246,49 @@ impl<'a> ScheduleBuilder<'a> {
         change
     }
 
+    /// Moves tasks to free gaps starting before their current start.
+    /// Only the start of every gap after the release time of a task is tried. The gaps of every
+    /// machine are tried in order and the last one the task fits into is used,
+    /// so it's not necessarily the earliest one.
     fn compact(&mut self) -> bool {
         let mut change = false;
 
         for &task in &self.tasks {
             if let Some((machine, time)) = self.scheduled.get(&task.0).copied() {
+                if !self.check_removal(task.0) {
+                    continue;
+                }
+
                 let mut best_machine = machine;
                 let mut best_time = time;
 
-                for machine in 0..self.matrix[0].len() {
-                    let mut free_time = 0;
-
-                    for time in 0..best_time + task.1.processing_time - 1 {
-                        if self.matrix[time as usize][machine].is_none() {
-                            free_time += 1;
-
-                            if free_time == task.1.processing_time
-                                && self.check_conflicts(&task, time - free_time + 1)
-                            {
-                                best_time = time - free_time + 1;
-                                best_machine = machine;
-                            }
-                        } else {
-                            free_time = 0;
+                let release_time = self.instance.release_time(task.0);
+
+                for machine in 0..self.timelines.len() {
+                    if !self.instance.is_eligible(task.0, machine) {
+                        continue;
+                    }
+
+                    let processing_time = self.instance.processing_time(task.0, machine);
+                    let end_time = (best_time + processing_time - 1).min(self.instance.deadline);
+
+                    for (start, end) in self.timelines[machine].gaps(release_time, end_time) {
+                        if start + processing_time <= end
+                            && self.instance.fits(task.0, machine, start)
+                            && self.check_conflicts(&task, start, machine)
+                            && self.check_setups(task.0, start, machine, task.0)
+                        {
+                            best_time = start;
+                            best_machine = machine;
                         }
                     }
                 }
 
                 if best_time < time {
-                    for instant in time..(time + task.1.processing_time) {
-                        self.matrix[instant as usize][machine] = None;
-                    }
+                    self.timelines[machine].free(time, task.0);
 
-                    for instant in best_time..(best_time + task.1.processing_time) {
-                        self.matrix[instant as usize][best_machine] = Some(task.0);
-                    }
+                    let end_time = best_time + self.instance.processing_time(task.0, best_machine);
+                    self.timelines[best_machine].occupy(best_time, end_time, task.0);
 
                     self.scheduled.insert(task.0, (best_machine, best_time));
                     change = true;
@@ -148,54 +300,105 @@ impl<'a> ScheduleBuilder<'a> {
     }
 
     fn check_time(&self, time: u64, machine: usize, task: &TaskWithId) -> bool {
-        if time + task.1.processing_time > self.matrix.len() as u64 {
-            return false;
-        }
+        self.instance.fits(task.0, machine, time)
+            && self.timelines[machine]
+                .is_free(time, time + self.instance.processing_time(task.0, machine))
+    }
 
-        for instant in time..(time + task.1.processing_time) {
-            if self.matrix[instant as usize][machine].is_some() {
-                return false;
+    fn check_conflicts(&self, task: &TaskWithId, time: u64, machine: usize) -> bool {
+        let end_time = time + self.instance.processing_time(task.0, machine);
+
+        for conflict in self.instance.conflicts(task.0) {
+            if let Some(&(other_machine, other_time)) = self.scheduled.get(&conflict) {
+                let other_end_time =
+                    other_time + self.instance.processing_time(conflict, other_machine);
+                if time < other_end_time && other_time < end_time {
+                    return false;
+                }
             }
         }
 
         true
     }
 
-    fn check_conflicts(&self, task: &TaskWithId, time: u64) -> bool {
-        for &conflict in self.instance.graph.conflicts(task.0) {
-            if let Some(&(_, other_time)) = self.scheduled.get(&conflict) {
-                let other_task = self.instance.tasks[conflict];
-                if time < other_time + other_task.processing_time
-                    && other_time < time + task.1.processing_time
-                {
-                    return false;
-                }
-            }
+    /// Checks the setups between the task started at the given time and its neighbours on the machine.
+    /// The ignored task is treated as not scheduled.
+    fn check_setups(&self, task: usize, time: u64, machine: usize, ignored: usize) -> bool {
+        if self.instance.setup_times.is_empty() {
+            return true;
         }
 
-        true
+        let (previous, next) = self.neighbours(machine, time, ignored);
+        let end_time = time + self.instance.processing_time(task, machine);
+
+        previous.iter().all(|&(other, other_time)| {
+            other_time
+                + self.instance.processing_time(other, machine)
+                + self.instance.setup_time(Some(other), task)
+                <= time
+        }) && next.iter().all(|&(other, other_time)| {
+            end_time + self.instance.setup_time(Some(task), other) <= other_time
+        })
     }
 
-    fn check_hole(&self, task: &TaskWithId, new_task: &TaskWithId) -> bool {
-        if task.1.processing_time >= new_task.1.processing_time {
+    /// Checks the setup between the neighbours of the task after it's removed from its machine.
+    fn check_removal(&self, task: usize) -> bool {
+        if self.instance.setup_times.is_empty() {
             return true;
         }
 
-        let &(machine, time) = self.scheduled.get(&task.0).unwrap();
+        let &(machine, time) = self.scheduled.get(&task).unwrap();
 
-        if time + new_task.1.processing_time > self.matrix.len() as u64 {
-            return false;
+        match self.neighbours(machine, time, task) {
+            (Some((previous, previous_time)), Some((next, next_time))) => {
+                previous_time
+                    + self.instance.processing_time(previous, machine)
+                    + self.instance.setup_time(Some(previous), next)
+                    <= next_time
+            }
+            _ => true,
         }
+    }
 
-        let start = (time + task.1.processing_time) as usize;
-        let end = (time + new_task.1.processing_time) as usize;
-        for instant in &self.matrix[start..end] {
-            if instant[machine].is_some() {
-                return false;
+    /// Returns the last task started before and the first task started after the given time on the machine.
+    fn neighbours(
+        &self,
+        machine: usize,
+        time: u64,
+        ignored: usize,
+    ) -> (Option<Placement>, Option<Placement>) {
+        let mut previous: Option<Placement> = None;
+        let mut next: Option<Placement> = None;
+
+        for (&other, &(other_machine, other_time)) in &self.scheduled {
+            if other == ignored || other_machine != machine {
+                continue;
+            }
+
+            if other_time < time {
+                if previous
+                    .iter()
+                    .all(|&(_, previous_time)| other_time > previous_time)
+                {
+                    previous = Some((other, other_time));
+                }
+            } else if next.iter().all(|&(_, next_time)| other_time < next_time) {
+                next = Some((other, other_time));
             }
         }
 
-        true
+        (previous, next)
+    }
+
+    fn check_hole(&self, task: &TaskWithId, new_task: &TaskWithId) -> bool {
+        let &(machine, time) = self.scheduled.get(&task.0).unwrap();
+        let processing_time = self.instance.processing_time(task.0, machine);
+        let new_processing_time = self.instance.processing_time(new_task.0, machine);
+
+        processing_time >= new_processing_time
+            || (time + new_processing_time <= self.instance.deadline
+                && self.timelines[machine]
+                    .is_free(time + processing_time, time + new_processing_time))
     }
 }
 
@@ -211,38 +414,179 @@ impl<'a> From<ScheduleBuilder<'a>> for Schedule<'a> {
     }
 }
 
-pub fn tresoldi(instance: &Instance) -> Schedule {
-    let mut rng = thread_rng();
-    let mut best_solution = ScheduleBuilder::empty(instance);
+/// How a new iteration of the Tresoldi scheduler chooses its task order.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum Restart {
+    /// Every iteration starts from a new random order.
+    Random,
+    /// Every iteration starts from the order of the best found solution
+    /// with the given number of random swaps.
+    Perturb { swaps: usize },
+}
 
-    for _ in 0..ITERATIONS {
-        let mut solution = ScheduleBuilder::random(instance, &mut rng);
+/// Tresoldi matheuristic scheduler.
+///
+/// Every iteration greedily inserts tasks in a task order, replaces scheduled tasks
+/// with better unscheduled ones and compacts the schedule until nothing changes.
+/// Runs with the same seed produce the same schedule unless the time limit is reached
+/// or the run is cancelled.
+/// Only the weight objective is optimised, other objectives and instances with cumulative resources
+/// are scheduled by the list algorithm.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Tresoldi {
+    /// Number of iterations.
+    pub iterations: usize,
+    /// Seed of the random generator. A random seed is used if it's not given.
+    pub seed: Option<u64>,
+    /// Task order of new iterations.
+    pub restart: Restart,
+    /// Wall-clock limit of the search in milliseconds.
+    pub time_limit: Option<u64>,
+}
 
-        loop {
-            let mut change = solution.greedy_insert();
-            change |= solution.local_search();
-            change |= solution.compact();
+impl Default for Tresoldi {
+    fn default() -> Self {
+        Self {
+            iterations: 10,
+            seed: None,
+            restart: Restart::Random,
+            time_limit: None,
+        }
+    }
+}
 
-            if !change {
+impl Tresoldi {
+    /// Returns whether the instance is scheduled by the list algorithm instead of the search,
+    /// which is the case for minimised objectives and instances with cumulative resources.
+    pub fn uses_list_algorithm(instance: &Instance) -> bool {
+        instance.objective.is_minimised() || !instance.demands.is_empty()
+    }
+}
+
+impl Scheduler for Tresoldi {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        if Self::uses_list_algorithm(instance) {
+            return list_algorithm(instance);
+        }
+
+        let mut tracker = Tracker::new(observer, self.time_limit);
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        let frozen_score = Schedule::new(instance).calculate_score();
+        let mut best_solution = ScheduleBuilder::empty(instance);
+
+        for _ in 0..self.iterations {
+            if tracker.should_stop() {
                 break;
             }
-        }
 
-        if solution.score > best_solution.score {
-            best_solution = solution;
+            let mut solution = match self.restart {
+                Restart::Perturb { swaps } if !best_solution.tasks.is_empty() => {
+                    best_solution.perturbed(swaps, &mut rng)
+                }
+                _ => ScheduleBuilder::random(instance, &mut rng),
+            };
+
+            loop {
+                let mut change = solution.greedy_insert();
+                change |= solution.local_search();
+                change |= solution.compact();
+
+                if !change || tracker.should_stop() {
+                    break;
+                }
+            }
+
+            if tracker.iteration((frozen_score + solution.score) as i64) {
+                best_solution = solution;
+            }
         }
+
+        best_solution.into()
     }
+}
 
-    best_solution.into()
+/// Runs the Tresoldi scheduler with the default parameters.
+pub fn tresoldi(instance: &Instance) -> Schedule<'_> {
+    Tresoldi::default().schedule(instance)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schedulers::test_utils::run_test_files;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
 
     #[test]
     fn test_tresoldi() {
         run_test_files(tresoldi).expect("Error running tests")
     }
+
+    #[test]
+    fn test_tresoldi_seeded() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let tresoldi = Tresoldi {
+            iterations: 5,
+            seed: Some(7),
+            restart: Restart::Perturb { swaps: 3 },
+            ..Tresoldi::default()
+        };
+
+        let first = tresoldi.clone().schedule(&instance);
+        let second = tresoldi.schedule(&instance);
+
+        assert!(first.verify());
+        for task in 0..instance.tasks.len() {
+            assert_eq!(first.get_schedule(task), second.get_schedule(task));
+        }
+    }
+
+    #[test]
+    fn test_tresoldi_long_horizon() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.deadline = 1_000_000_000;
+
+        let schedule = tresoldi(&instance);
+
+        assert!(schedule.verify());
+        assert_eq!(
+            schedule.calculate_score(),
+            instance.tasks.iter().map(|task| task.weight).sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_timeline() {
+        let mut timeline = Timeline::default();
+        timeline.occupy(10, 20, 0);
+        timeline.occupy(30, 40, BLOCKED);
+
+        assert!(timeline.is_free(0, 10));
+        assert!(!timeline.is_free(15, 16));
+        assert!(!timeline.is_free(19, 31));
+        assert_eq!(timeline.gaps(5, 50), vec![(5, 10), (20, 30), (40, 50)]);
+
+        timeline.free(30, 0);
+        assert!(!timeline.is_free(30, 31));
+        timeline.free(10, 0);
+        assert_eq!(timeline.gaps(0, 35), vec![(0, 30)]);
+
+        timeline.occupy(0, 100, BLOCKED);
+        timeline.occupy(50, 60, BLOCKED);
+        timeline.occupy(50, 150, BLOCKED);
+        assert!(!timeline.is_free(120, 130));
+        assert!(!timeline.is_free(99, 101));
+        assert!(timeline.is_free(150, 160));
+        assert_eq!(timeline.gaps(0, 200), vec![(150, 200)]);
+    }
 }
@@ -1,104 +1,408 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
 
+use crate::schedulers::observer::Tracker;
 use crate::util::ScheduleBuilder;
-use crate::{Instance, Schedule};
+use crate::{Instance, Observer, Schedule, Scheduler};
 
-mod init;
+pub(crate) mod init;
 mod neighborhoods;
 
-fn neighborhood_search(mut schedule: ScheduleBuilder) -> ScheduleBuilder {
-    let factories = [
-        neighborhoods::swap_single_machine,
-        neighborhoods::move_single_machine,
-        neighborhoods::swap_two_machines,
-        neighborhoods::move_two_machines,
-        neighborhoods::replace_with_tardy,
-        neighborhoods::add_tardy,
-    ];
+pub(crate) use neighborhoods::Step;
+
+/// A neighborhood searched by the variable neighborhood search.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum Neighborhood {
+    /// Swaps two tasks on the same machine.
+    SwapSingleMachine,
+    /// Moves a task to another position on the same machine.
+    MoveSingleMachine,
+    /// Swaps two tasks between two machines.
+    SwapTwoMachines,
+    /// Moves a task to another machine.
+    MoveTwoMachines,
+    /// Replaces a scheduled task with a tardy one.
+    ReplaceWithTardy,
+    /// Adds a tardy task to a machine.
+    AddTardy,
+}
 
-    let mut k = 0;
+impl Neighborhood {
+    /// The default order of the neighborhoods.
+    pub const ALL: [Neighborhood; 6] = [
+        Neighborhood::SwapSingleMachine,
+        Neighborhood::MoveSingleMachine,
+        Neighborhood::SwapTwoMachines,
+        Neighborhood::MoveTwoMachines,
+        Neighborhood::ReplaceWithTardy,
+        Neighborhood::AddTardy,
+    ];
 
-    while k < factories.len() {
-        let mut best_score = schedule.calculate_score();
-        let mut best_schedule = None;
+    /// Returns an iterator over all moves of the neighborhood.
+    pub(crate) fn search<'b>(
+        self,
+        schedule: &'b ScheduleBuilder,
+    ) -> Box<neighborhoods::Neighborhood<'b>> {
+        match self {
+            Neighborhood::SwapSingleMachine => neighborhoods::swap_single_machine(schedule),
+            Neighborhood::MoveSingleMachine => neighborhoods::move_single_machine(schedule),
+            Neighborhood::SwapTwoMachines => neighborhoods::swap_two_machines(schedule),
+            Neighborhood::MoveTwoMachines => neighborhoods::move_two_machines(schedule),
+            Neighborhood::ReplaceWithTardy => neighborhoods::replace_with_tardy(schedule),
+            Neighborhood::AddTardy => neighborhoods::add_tardy(schedule),
+        }
+    }
 
-        for schedule in factories[k](&schedule) {
-            let score = schedule.calculate_score();
-            if score > best_score {
-                best_score = score;
-                best_schedule = Some(schedule);
+    /// Returns a random move of the neighborhood.
+    /// It returns None if the drawn move is not possible.
+    pub(crate) fn random<R: Rng>(self, schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+        match self {
+            Neighborhood::SwapSingleMachine => {
+                neighborhoods::random_swap_single_machine(schedule, rng)
             }
+            Neighborhood::MoveSingleMachine => {
+                neighborhoods::random_move_single_machine(schedule, rng)
+            }
+            Neighborhood::SwapTwoMachines => neighborhoods::random_swap_two_machines(schedule, rng),
+            Neighborhood::MoveTwoMachines => neighborhoods::random_move_two_machines(schedule, rng),
+            Neighborhood::ReplaceWithTardy => {
+                neighborhoods::random_replace_with_tardy(schedule, rng)
+            }
+            Neighborhood::AddTardy => neighborhoods::random_add_tardy(schedule, rng),
         }
+    }
+}
 
-        if let Some(best_schedule) = best_schedule {
-            schedule = best_schedule;
-            k = 0;
-        } else {
-            k += 1;
+/// Variable neighborhood search scheduler.
+///
+/// Starts from a list schedule and alternates a local search over the neighborhoods
+/// with random shakes of the best found schedule.
+/// Runs with the same seed produce the same schedule unless the time limit is reached
+/// or the run is cancelled.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Vns {
+    /// Seed of the random generator. A random seed is used if it's not given.
+    pub seed: Option<u64>,
+    /// Number of shakes of the best found schedule.
+    pub shakes: usize,
+    /// Fraction of the tasks moved in a single shake. At least one task is moved.
+    pub perturbation: f64,
+    /// Wall-clock limit of the search in milliseconds.
+    pub time_limit: Option<u64>,
+    /// Neighborhoods searched in the given order.
+    pub neighborhoods: Vec<Neighborhood>,
+}
+
+impl Default for Vns {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            shakes: 10,
+            perturbation: 0.05,
+            time_limit: None,
+            neighborhoods: Neighborhood::ALL.to_vec(),
         }
     }
+}
+
+impl Scheduler for Vns {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
+        self.run(instance, observer, MoveEvaluation::Incremental)
+    }
+}
 
-    schedule
+/// How the local search scores the moves of a neighborhood.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum MoveEvaluation {
+    /// Applies the move in a trial and rolls it back, rescoring only the touched tasks.
+    Incremental,
+    /// Applies the move to a clone of the schedule and rescores every task.
+    FullRescore,
 }
 
-pub fn vns(instance: &Instance) -> Schedule {
-    let mut schedule = neighborhood_search(init::schedule(instance));
-    let mut best_score = schedule.calculate_score();
+impl Vns {
+    /// Schedules the instance scoring every move on a full clone of the schedule.
+    /// It produces the same schedule as [`Scheduler::schedule`] only slower,
+    /// it's the baseline of the benchmark of the incremental evaluation.
+    #[doc(hidden)]
+    pub fn schedule_full_rescore(self, instance: &Instance) -> Schedule<'_> {
+        self.run(instance, &(), MoveEvaluation::FullRescore)
+    }
+
+    fn run<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+        evaluation: MoveEvaluation,
+    ) -> Schedule<'a> {
+        let mut tracker = Tracker::new(observer, self.time_limit);
+        let mut rng = match self.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let mut schedule = self.neighborhood_search(init::schedule(instance), evaluation, &|| {
+            tracker.should_stop()
+        });
+        tracker.iteration(schedule.objective_score());
 
-    let mut rng = rand::thread_rng();
+        let perturbation = (instance.tasks.len() as f64 * self.perturbation) as usize;
 
-    for _ in 0..10 {
-        let mut new_schedule = schedule.clone();
+        for _ in 0..self.shakes {
+            if tracker.should_stop() {
+                break;
+            }
+
+            let mut new_schedule = schedule.clone();
+
+            for _ in 0..perturbation.max(1).min(instance.tasks.len()) {
+                shake(&mut new_schedule, &mut rng);
+            }
 
-        for _ in 0..(instance.tasks.len() / 20).max(1) {
-            let task = rng.gen_range(0..instance.tasks.len());
-            let task_machine = new_schedule.get_schedule(task).map(|info| info.processor);
+            let new_schedule =
+                self.neighborhood_search(new_schedule, evaluation, &|| tracker.should_stop());
 
-            new_schedule.reorganize_schedule(|machines, tardy_tasks| {
-                let mut machine_fixings = Vec::with_capacity(2);
+            if tracker.iteration(new_schedule.objective_score()) {
+                schedule = new_schedule;
+            }
+        }
 
-                match task_machine {
-                    Some(machine) => {
-                        if let Some(pos) = machines[machine].iter().position(|&id| id == task) {
-                            machine_fixings.push((machine, pos));
-                        }
-                        machines[machine].retain(|&id| id != task);
+        schedule.into()
+    }
+
+    fn neighborhood_search<'a>(
+        &self,
+        mut schedule: ScheduleBuilder<'a>,
+        evaluation: MoveEvaluation,
+        should_stop: &impl Fn() -> bool,
+    ) -> ScheduleBuilder<'a> {
+        let mut k = 0;
+
+        while k < self.neighborhoods.len() && !should_stop() {
+            let mut best_score = schedule.objective_score();
+            let mut best_move = None;
+
+            let moves: Vec<Step> = self.neighborhoods[k].search(&schedule).collect();
+            for step in moves {
+                let score = match evaluation {
+                    MoveEvaluation::Incremental => schedule.trial(
+                        |schedule| step.apply(schedule),
+                        |schedule| schedule.objective_score(),
+                    ),
+                    MoveEvaluation::FullRescore => {
+                        let mut neighbor = schedule.clone();
+                        step.apply(&mut neighbor);
+                        let objective = neighbor.instance().objective;
+                        objective.score(&neighbor.into())
                     }
-                    None => tardy_tasks.retain(|&id| id != task),
+                };
+                if score > best_score {
+                    best_score = score;
+                    best_move = Some(step);
                 }
+            }
 
-                let new_machine = rng.gen_range(0..instance.processors);
-                let new_position = rng.gen_range(0..machines[new_machine].len() + 1);
-                machines[new_machine].insert(new_position, task);
+            if let Some(best_move) = best_move {
+                best_move.apply(&mut schedule);
+                k = 0;
+            } else {
+                k += 1;
+            }
+        }
 
-                match task_machine.filter(|&machine| machine == new_machine) {
-                    Some(_) => machine_fixings[0].1 = new_position.min(machine_fixings[0].1),
-                    None => machine_fixings.push((new_machine, new_position)),
-                }
+        schedule
+    }
+}
+
+/// Moves a random task to a random position on a random eligible machine.
+/// Frozen tasks are never moved.
+fn shake(schedule: &mut ScheduleBuilder, rng: &mut StdRng) {
+    let instance = schedule.instance();
+    let task = rng.gen_range(0..instance.tasks.len());
+    if instance.is_frozen(task) {
+        return;
+    }
+
+    let task_machine = schedule.get_schedule(task).map(|info| info.processor);
+    let eligible_machines: Vec<usize> = (0..instance.processors)
+        .filter(|&machine| instance.is_eligible(task, machine))
+        .collect();
+
+    let mut changed_machines = eligible_machines.clone();
+    changed_machines.extend(task_machine);
+
+    schedule.reorganize_schedule(&changed_machines, |machines, tardy_tasks| {
+        let mut machine_fixings = Vec::with_capacity(2);
 
-                (machine_fixings, vec![])
-            });
+        match task_machine {
+            Some(machine) => {
+                if let Some(pos) = machines[machine].iter().position(|&id| id == task) {
+                    machine_fixings.push((machine, pos));
+                }
+                machines[machine].retain(|&id| id != task);
+            }
+            None => tardy_tasks.retain(|&id| id != task),
         }
 
-        let new_schedule = neighborhood_search(new_schedule);
-        let new_score = new_schedule.calculate_score();
+        let Some(&new_machine) = eligible_machines.choose(rng) else {
+            tardy_tasks.push(task);
+            return (machine_fixings, vec![task]);
+        };
+        let new_position = rng.gen_range(0..machines[new_machine].len() + 1);
+        machines[new_machine].insert(new_position, task);
 
-        if new_score > best_score {
-            best_score = new_score;
-            schedule = new_schedule;
+        match task_machine.filter(|&machine| machine == new_machine) {
+            Some(_) => machine_fixings[0].1 = new_position.min(machine_fixings[0].1),
+            None => machine_fixings.push((new_machine, new_position)),
         }
-    }
 
-    schedule.into()
+        (machine_fixings, vec![])
+    });
+}
+
+/// Runs the variable neighborhood search with the default parameters.
+pub fn vns(instance: &Instance) -> Schedule<'_> {
+    Vns::default().schedule(instance)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schedulers::test_utils::run_test_files;
+    use crate::schedulers::test_utils::{read_test_file, run_test_files};
+    use crate::{CumulativeResource, Objective};
 
     #[test]
     fn test_vns() {
         run_test_files(vns).expect("Error running tests")
     }
+
+    #[test]
+    fn test_vns_seeded() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let vns = Vns {
+            seed: Some(42),
+            neighborhoods: vec![Neighborhood::AddTardy, Neighborhood::SwapTwoMachines],
+            ..Vns::default()
+        };
+
+        let first = vns.clone().schedule(&instance);
+        let second = vns.schedule(&instance);
+
+        assert!(first.verify());
+        for task in 0..instance.tasks.len() {
+            assert_eq!(first.get_schedule(task), second.get_schedule(task));
+        }
+    }
+
+    #[test]
+    fn test_full_rescore() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let vns = Vns {
+            seed: Some(7),
+            shakes: 3,
+            ..Vns::default()
+        };
+
+        let incremental = vns.clone().schedule(&instance);
+        let full_rescore = vns.schedule_full_rescore(&instance);
+
+        for task in 0..instance.tasks.len() {
+            assert_eq!(
+                incremental.get_schedule(task),
+                full_rescore.get_schedule(task)
+            );
+        }
+    }
+
+    #[test]
+    fn test_trial_moves() {
+        let mut instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+
+        for objective in [
+            Objective::Weight,
+            Objective::Makespan,
+            Objective::WeightedCompletion,
+            Objective::WeightedTardiness,
+        ] {
+            instance.objective = objective;
+            let mut schedule = init::schedule(&instance);
+            let fingerprint = schedule.fingerprint();
+            let score = schedule.objective_score();
+
+            for neighborhood in Neighborhood::ALL {
+                let moves: Vec<Step> = neighborhood.search(&schedule).collect();
+                for step in moves {
+                    let (trial_score, trial_fingerprint) = schedule.trial(
+                        |schedule| step.apply(schedule),
+                        |schedule| (schedule.objective_score(), schedule.fingerprint()),
+                    );
+
+                    let mut applied = schedule.clone();
+                    step.apply(&mut applied);
+                    assert_eq!(trial_score, applied.objective_score());
+                    assert_eq!(trial_fingerprint, applied.fingerprint());
+                    assert_eq!(
+                        applied.objective_score(),
+                        objective.score(&Schedule::from(applied))
+                    );
+
+                    assert_eq!(schedule.fingerprint(), fingerprint);
+                    assert_eq!(schedule.objective_score(), score);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_vns_objectives() {
+        let mut instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+
+        for objective in [
+            Objective::Makespan,
+            Objective::WeightedCompletion,
+            Objective::WeightedTardiness,
+        ] {
+            instance.objective = objective;
+            let schedule = Vns {
+                seed: Some(5),
+                ..Vns::default()
+            }
+            .schedule(&instance);
+
+            assert_eq!(schedule.violations(), vec![]);
+            let initial = Schedule::from(init::schedule(&instance));
+            assert!(objective.value(&schedule) <= objective.value(&initial));
+        }
+    }
+
+    #[test]
+    fn test_vns_capacities() {
+        let mut instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        instance.cumulative_resources = vec![CumulativeResource::new("operators", 2)];
+        instance.demands = (0..instance.tasks.len())
+            .map(|task| (task, 0, 1 + task as u64 % 2))
+            .collect();
+
+        for objective in [Objective::Weight, Objective::Makespan] {
+            instance.objective = objective;
+            let schedule = Vns {
+                seed: Some(5),
+                ..Vns::default()
+            }
+            .schedule(&instance);
+
+            assert_eq!(schedule.violations(), vec![]);
+        }
+    }
 }
@@ -1,8 +1,43 @@
+use rand::Rng;
+
 use crate::util::ScheduleBuilder;
 
 /// Neighborhood trait represents a neighborhood of a schedule.
-/// It's used to generate new schedules from a given schedule.
-pub type Neighborhood<'a, 'b> = dyn Iterator<Item = ScheduleBuilder<'a>> + 'b;
+/// It's used to generate moves to new schedules from a given schedule.
+pub type Neighborhood<'b> = dyn Iterator<Item = Step> + 'b;
+
+/// A move to a neighbor of a schedule.
+/// Moves are cheap to generate and are applied to a schedule builder only when needed,
+/// e.g. the best one after evaluating all of them with [`ScheduleBuilder::trial`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Step {
+    /// Swaps the tasks at two positions of the machine.
+    SwapSingle(usize, usize, usize),
+    /// Moves the task at a position of the machine to another position.
+    MoveSingle(usize, usize, usize),
+    /// Swaps tasks at the given (machine, position) pairs.
+    SwapTwo((usize, usize), (usize, usize)),
+    /// Moves the task at the first (machine, position) pair to the second one.
+    MoveTwo((usize, usize), (usize, usize)),
+    /// Replaces the task at a position of the machine with the given tardy task.
+    ReplaceTardy(usize, usize, usize),
+    /// Inserts the given tardy task at a position of the machine.
+    InsertTardy(usize, usize, usize),
+}
+
+impl Step {
+    /// Applies the move to the schedule builder.
+    pub fn apply(self, builder: &mut ScheduleBuilder) {
+        match self {
+            Step::SwapSingle(machine, i, j) => swap_single(builder, machine, i, j),
+            Step::MoveSingle(machine, i, j) => move_single(builder, machine, i, j),
+            Step::SwapTwo(first, second) => swap_two(builder, first, second),
+            Step::MoveTwo(first, second) => move_two(builder, first, second),
+            Step::ReplaceTardy(machine, i, j) => replace_tardy(builder, machine, i, j),
+            Step::InsertTardy(machine, i, j) => insert_tardy(builder, machine, i, j),
+        }
+    }
+}
 
 /// Neighborhood that swaps two tasks on the same machine.
 pub struct SwapSingleMachine<'a, 'b> {
@@ -13,7 +48,7 @@ pub struct SwapSingleMachine<'a, 'b> {
 }
 
 /// Creates a new instance of SwapSingleMachine neighborhood.
-pub fn swap_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+pub fn swap_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>> {
     Box::new(SwapSingleMachine {
         schedule,
         machine: 0,
@@ -23,22 +58,17 @@ pub fn swap_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Nei
 }
 
 impl<'a, 'b> Iterator for SwapSingleMachine<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+    type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i + 1 < self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.machine_tasks_len(self.machine) {
-                    let mut builder = self.schedule.clone();
-
-                    builder.reorganize_schedule(|machines, _| {
-                        machines[self.machine].swap(self.i, self.j);
-                        (vec![(self.machine, self.i)], vec![])
-                    });
+                    let step = Step::SwapSingle(self.machine, self.i, self.j);
 
                     self.j += 1;
 
-                    return Some(builder);
+                    return Some(step);
                 }
                 self.i += 1;
             }
@@ -57,7 +87,7 @@ pub struct MoveSingleMachine<'a, 'b> {
 }
 
 /// Creates a new instance of MoveSingleMachine neighborhood.
-pub fn move_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+pub fn move_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>> {
     Box::new(MoveSingleMachine {
         schedule,
         machine: 0,
@@ -67,23 +97,17 @@ pub fn move_single_machine<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Nei
 }
 
 impl<'a, 'b> Iterator for MoveSingleMachine<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+    type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i + 1 < self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.machine_tasks_len(self.machine) {
-                    let mut builder = self.schedule.clone();
-
-                    builder.reorganize_schedule(|machines, _| {
-                        let task = machines[self.machine].remove(self.i);
-                        machines[self.machine].insert(self.j, task);
-                        (vec![(self.machine, self.i.min(self.j))], vec![])
-                    });
+                    let step = Step::MoveSingle(self.machine, self.i, self.j);
 
                     self.j += 1;
 
-                    return Some(builder);
+                    return Some(step);
                 }
                 self.i += 1;
             }
@@ -103,7 +127,7 @@ pub struct SwapTwoMachines<'a, 'b> {
 }
 
 /// Creates a new instance of SwapTwoMachines neighborhood.
-pub fn swap_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+pub fn swap_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>> {
     Box::new(SwapTwoMachines {
         schedule,
         first: 0,
@@ -114,26 +138,29 @@ pub fn swap_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neigh
 }
 
 impl<'a, 'b> Iterator for SwapTwoMachines<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+    type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.first + 1 < self.schedule.machines_len() {
             while self.second < self.schedule.machines_len() {
                 while self.i < self.schedule.machine_tasks_len(self.first) {
                     if self.j < self.schedule.machine_tasks_len(self.second) {
-                        let mut builder = self.schedule.clone();
+                        let instance = self.schedule.instance();
+                        let first_task = self.schedule.machine_task(self.first, self.i);
+                        let second_task = self.schedule.machine_task(self.second, self.j);
 
-                        builder.reorganize_schedule(|machines, _| {
-                            let value = machines[self.first][self.i];
-                            machines[self.first][self.i] = machines[self.second][self.j];
-                            machines[self.second][self.j] = value;
+                        if !instance.is_eligible(first_task, self.second)
+                            || !instance.is_eligible(second_task, self.first)
+                        {
+                            self.j += 1;
+                            continue;
+                        }
 
-                            (vec![(self.first, self.i), (self.second, self.j)], vec![])
-                        });
+                        let step = Step::SwapTwo((self.first, self.i), (self.second, self.j));
 
                         self.j += 1;
 
-                        return Some(builder);
+                        return Some(step);
                     }
                     self.i += 1;
                 }
@@ -155,7 +182,7 @@ pub struct MoveTwoMachines<'a, 'b> {
 }
 
 /// Creates a new instance of MoveTwoMachines neighborhood.
-pub fn move_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+pub fn move_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>> {
     Box::new(MoveTwoMachines {
         schedule,
         first: 0,
@@ -166,25 +193,25 @@ pub fn move_two_machines<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neigh
 }
 
 impl<'a, 'b> Iterator for MoveTwoMachines<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+    type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.first + 1 < self.schedule.machines_len() {
             while self.second < self.schedule.machines_len() {
                 while self.i < self.schedule.machine_tasks_len(self.first) {
                     if self.j <= self.schedule.machine_tasks_len(self.second) {
-                        let mut builder = self.schedule.clone();
+                        let task = self.schedule.machine_task(self.first, self.i);
 
-                        builder.reorganize_schedule(|machines, _| {
-                            let value = machines[self.first].remove(self.i);
-                            machines[self.second].insert(self.j, value);
+                        if !self.schedule.instance().is_eligible(task, self.second) {
+                            self.j += 1;
+                            continue;
+                        }
 
-                            (vec![(self.first, self.i), (self.second, self.j)], vec![])
-                        });
+                        let step = Step::MoveTwo((self.first, self.i), (self.second, self.j));
 
                         self.j += 1;
 
-                        return Some(builder);
+                        return Some(step);
                     }
                     self.i += 1;
                 }
@@ -205,7 +232,7 @@ pub struct ReplaceWithTardy<'a, 'b> {
 }
 
 /// Creates a new instance of ReplaceWithTardy neighborhood.
-pub fn replace_with_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+pub fn replace_with_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>> {
     Box::new(ReplaceWithTardy {
         schedule,
         machine: 0,
@@ -215,26 +242,24 @@ pub fn replace_with_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neig
 }
 
 impl<'a, 'b> Iterator for ReplaceWithTardy<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+    type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i < self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.tardy_len() {
-                    let mut builder = self.schedule.clone();
+                    let task = self.schedule.tardy_task(self.j);
 
-                    builder.reorganize_schedule(|machines, tardy_tasks| {
-                        std::mem::swap(
-                            &mut machines[self.machine][self.i],
-                            &mut tardy_tasks[self.j],
-                        );
+                    if !self.schedule.instance().is_eligible(task, self.machine) {
+                        self.j += 1;
+                        continue;
+                    }
 
-                        (vec![(self.machine, self.i)], vec![tardy_tasks[self.j]])
-                    });
+                    let step = Step::ReplaceTardy(self.machine, self.i, self.j);
 
                     self.j += 1;
 
-                    return Some(builder);
+                    return Some(step);
                 }
                 self.i += 1;
             }
@@ -253,7 +278,7 @@ pub struct AddTardy<'a, 'b> {
 }
 
 /// Creates a new instance of AddTardy neighborhood.
-pub fn add_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'a, 'b>> {
+pub fn add_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<'b>> {
     Box::new(AddTardy {
         schedule,
         machine: 0,
@@ -263,24 +288,24 @@ pub fn add_tardy<'a, 'b>(schedule: &'b ScheduleBuilder<'a>) -> Box<Neighborhood<
 }
 
 impl<'a, 'b> Iterator for AddTardy<'a, 'b> {
-    type Item = ScheduleBuilder<'a>;
+    type Item = Step;
 
     fn next(&mut self) -> Option<Self::Item> {
         while self.machine < self.schedule.machines_len() {
             while self.i <= self.schedule.machine_tasks_len(self.machine) {
                 if self.j < self.schedule.tardy_len() {
-                    let mut builder = self.schedule.clone();
+                    let task = self.schedule.tardy_task(self.j);
 
-                    builder.reorganize_schedule(|machines, tardy_tasks| {
-                        machines[self.machine].insert(self.i, tardy_tasks[self.j]);
-                        tardy_tasks.remove(self.j);
+                    if !self.schedule.instance().is_eligible(task, self.machine) {
+                        self.j += 1;
+                        continue;
+                    }
 
-                        (vec![(self.machine, self.i)], vec![])
-                    });
+                    let step = Step::InsertTardy(self.machine, self.i, self.j);
 
                     self.j += 1;
 
-                    return Some(builder);
+                    return Some(step);
                 }
                 self.i += 1;
             }
@@ -289,3 +314,183 @@ impl<'a, 'b> Iterator for AddTardy<'a, 'b> {
         None
     }
 }
+
+/// Swaps the tasks at positions `i` and `j` of the machine.
+fn swap_single(builder: &mut ScheduleBuilder, machine: usize, i: usize, j: usize) {
+    builder.reorganize_schedule(&[machine], |machines, _| {
+        machines[machine].swap(i, j);
+        (vec![(machine, i.min(j))], vec![])
+    });
+}
+
+/// Moves the task at position `i` of the machine to position `j`.
+fn move_single(builder: &mut ScheduleBuilder, machine: usize, i: usize, j: usize) {
+    builder.reorganize_schedule(&[machine], |machines, _| {
+        let task = machines[machine].remove(i);
+        machines[machine].insert(j, task);
+        (vec![(machine, i.min(j))], vec![])
+    });
+}
+
+/// Swaps tasks at the given positions of two different machines.
+fn swap_two(builder: &mut ScheduleBuilder, first: (usize, usize), second: (usize, usize)) {
+    builder.reorganize_schedule(&[first.0, second.0], |machines, _| {
+        let value = machines[first.0][first.1];
+        machines[first.0][first.1] = machines[second.0][second.1];
+        machines[second.0][second.1] = value;
+
+        (vec![first, second], vec![])
+    });
+}
+
+/// Moves the task at the first position to the second position on a different machine.
+fn move_two(builder: &mut ScheduleBuilder, first: (usize, usize), second: (usize, usize)) {
+    builder.reorganize_schedule(&[first.0, second.0], |machines, _| {
+        let value = machines[first.0].remove(first.1);
+        machines[second.0].insert(second.1, value);
+
+        (vec![first, second], vec![])
+    });
+}
+
+/// Replaces the task at position `i` of the machine with the tardy task `j`.
+fn replace_tardy(builder: &mut ScheduleBuilder, machine: usize, i: usize, j: usize) {
+    builder.reorganize_schedule(&[machine], |machines, tardy_tasks| {
+        std::mem::swap(&mut machines[machine][i], &mut tardy_tasks[j]);
+
+        (vec![(machine, i)], vec![tardy_tasks[j]])
+    });
+}
+
+/// Inserts the tardy task `j` at position `i` of the machine.
+fn insert_tardy(builder: &mut ScheduleBuilder, machine: usize, i: usize, j: usize) {
+    builder.reorganize_schedule(&[machine], |machines, tardy_tasks| {
+        machines[machine].insert(i, tardy_tasks[j]);
+        tardy_tasks.remove(j);
+
+        (vec![(machine, i)], vec![])
+    });
+}
+
+/// Returns a random machine with at least the given number of tasks.
+fn random_machine<R: Rng>(
+    schedule: &ScheduleBuilder,
+    min_tasks: usize,
+    rng: &mut R,
+) -> Option<usize> {
+    let machines: Vec<usize> = (0..schedule.machines_len())
+        .filter(|&machine| schedule.machine_tasks_len(machine) >= min_tasks)
+        .collect();
+    (!machines.is_empty()).then(|| machines[rng.gen_range(0..machines.len())])
+}
+
+/// Returns two random distinct positions `i < j` below the given length.
+fn random_pair<R: Rng>(len: usize, rng: &mut R) -> (usize, usize) {
+    let i = rng.gen_range(0..len - 1);
+    (i, rng.gen_range(i + 1..len))
+}
+
+/// Returns two random distinct machines `first < second` with the given minimum numbers of tasks.
+fn random_machine_pair<R: Rng>(
+    schedule: &ScheduleBuilder,
+    min_tasks: (usize, usize),
+    rng: &mut R,
+) -> Option<(usize, usize)> {
+    let first = random_machine(schedule, min_tasks.0, rng)?;
+    let second = random_machine(schedule, min_tasks.1, rng)?;
+    (first != second).then(|| (first.min(second), first.max(second)))
+}
+
+/// Returns a random move of the swap single machine neighborhood.
+pub fn random_swap_single_machine<R: Rng>(schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+    let machine = random_machine(schedule, 2, rng)?;
+    let (i, j) = random_pair(schedule.machine_tasks_len(machine), rng);
+    Some(Step::SwapSingle(machine, i, j))
+}
+
+/// Returns a random move of the move single machine neighborhood.
+pub fn random_move_single_machine<R: Rng>(schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+    let machine = random_machine(schedule, 2, rng)?;
+    let (i, j) = random_pair(schedule.machine_tasks_len(machine), rng);
+    Some(Step::MoveSingle(machine, i, j))
+}
+
+/// Returns a random move of the swap two machines neighborhood.
+/// It returns None if the drawn tasks are not eligible for the other machines.
+pub fn random_swap_two_machines<R: Rng>(schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+    let (first, second) = random_machine_pair(schedule, (1, 1), rng)?;
+    let i = rng.gen_range(0..schedule.machine_tasks_len(first));
+    let j = rng.gen_range(0..schedule.machine_tasks_len(second));
+    let instance = schedule.instance();
+
+    if !instance.is_eligible(schedule.machine_task(first, i), second)
+        || !instance.is_eligible(schedule.machine_task(second, j), first)
+    {
+        return None;
+    }
+
+    Some(Step::SwapTwo((first, i), (second, j)))
+}
+
+/// Returns a random move of the move two machines neighborhood.
+/// It returns None if the drawn task is not eligible for the other machine.
+pub fn random_move_two_machines<R: Rng>(schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+    let (first, second) = random_machine_pair(schedule, (1, 0), rng)?;
+    if schedule.machine_tasks_len(first) == 0 {
+        return None;
+    }
+
+    let i = rng.gen_range(0..schedule.machine_tasks_len(first));
+    let j = rng.gen_range(0..=schedule.machine_tasks_len(second));
+
+    if !schedule
+        .instance()
+        .is_eligible(schedule.machine_task(first, i), second)
+    {
+        return None;
+    }
+
+    Some(Step::MoveTwo((first, i), (second, j)))
+}
+
+/// Returns a random move of the replace with tardy neighborhood.
+/// It returns None if the drawn tardy task is not eligible for the machine.
+pub fn random_replace_with_tardy<R: Rng>(schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+    if schedule.tardy_len() == 0 {
+        return None;
+    }
+
+    let machine = random_machine(schedule, 1, rng)?;
+    let i = rng.gen_range(0..schedule.machine_tasks_len(machine));
+    let j = rng.gen_range(0..schedule.tardy_len());
+
+    if !schedule
+        .instance()
+        .is_eligible(schedule.tardy_task(j), machine)
+    {
+        return None;
+    }
+
+    Some(Step::ReplaceTardy(machine, i, j))
+}
+
+/// Returns a random move of the add tardy neighborhood.
+/// It returns None if the drawn tardy task is not eligible for the machine.
+pub fn random_add_tardy<R: Rng>(schedule: &ScheduleBuilder, rng: &mut R) -> Option<Step> {
+    if schedule.tardy_len() == 0 {
+        return None;
+    }
+
+    let machine = random_machine(schedule, 0, rng)?;
+    let i = rng.gen_range(0..=schedule.machine_tasks_len(machine));
+    let j = rng.gen_range(0..schedule.tardy_len());
+
+    if !schedule
+        .instance()
+        .is_eligible(schedule.tardy_task(j), machine)
+    {
+        return None;
+    }
+
+    Some(Step::InsertTardy(machine, i, j))
+}
@@ -10,6 +10,11 @@ use std::io::BufRead;
 pub struct Deserializer<'a, R: BufRead> {
     source: &'a mut R,
     buffer: VecDeque<String>,
+    line_end: bool,
+    /// Number of lines read so far.
+    line: usize,
+    /// Whether the optional field being read is omitted at the end of the line of its record.
+    omitted: bool,
 }
 
 impl<'a, R: BufRead> Deserializer<'a, R> {
@@ -18,6 +23,9 @@ impl<'a, R: BufRead> Deserializer<'a, R> {
         Self {
             source,
             buffer: VecDeque::new(),
+            line_end: false,
+            line: 0,
+            omitted: false,
         }
     }
 
@@ -26,7 +34,13 @@ impl<'a, R: BufRead> Deserializer<'a, R> {
             self.load_line()?;
         }
 
-        self.buffer.pop_front().ok_or(Error::EmptyLine)
+        let value = self.buffer.pop_front().ok_or(Error::EmptyLine);
+        self.line_end = self.buffer.is_empty();
+        value
+    }
+
+    fn end_of_input(&mut self) -> Result<bool> {
+        Ok(self.buffer.is_empty() && self.source.fill_buf()?.is_empty())
     }
 
     fn peek_next(&mut self) -> Result<&String> {
@@ -44,6 +58,8 @@ impl<'a, R: BufRead> Deserializer<'a, R> {
             return Err(Error::EndOfInput);
         }
 
+        self.line_end = false;
+        self.line += 1;
         let trimmed = line.trim();
 
         for line in trimmed.split(' ') {
@@ -147,8 +163,12 @@ impl<'a, R: BufRead> DeserializerTrait<'a> for &mut Deserializer<'a, R> {
         visitor.visit_byte_buf(buffer)
     }
 
+    /// Trailing optional fields of a struct or tuple omitted at the end of its line
+    /// are deserialized as None. Other optional values need an explicit `-`.
     fn deserialize_option<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
-        if self.peek_next()? == "-" {
+        if std::mem::take(&mut self.omitted) && !self.end_of_input()? {
+            visitor.visit_none()
+        } else if self.peek_next()? == "-" {
             self.next()?;
             visitor.visit_none()
         } else {
@@ -185,7 +205,7 @@ impl<'a, R: BufRead> DeserializerTrait<'a> for &mut Deserializer<'a, R> {
     }
 
     fn deserialize_tuple<V: Visitor<'a>>(self, _: usize, visitor: V) -> Result<V::Value> {
-        visitor.visit_seq(SimpleSeqAccess(self))
+        visitor.visit_seq(SimpleSeqAccess::new(self))
     }
 
     fn deserialize_tuple_struct<V: Visitor<'a>>(
@@ -194,7 +214,7 @@ impl<'a, R: BufRead> DeserializerTrait<'a> for &mut Deserializer<'a, R> {
         _: usize,
         visitor: V,
     ) -> Result<V::Value> {
-        visitor.visit_seq(SimpleSeqAccess(self))
+        visitor.visit_seq(SimpleSeqAccess::new(self))
     }
 
     fn deserialize_map<V: Visitor<'a>>(self, visitor: V) -> Result<V::Value> {
@@ -207,7 +227,7 @@ impl<'a, R: BufRead> DeserializerTrait<'a> for &mut Deserializer<'a, R> {
         _: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        visitor.visit_seq(SimpleSeqAccess(self))
+        visitor.visit_seq(SimpleSeqAccess::new(self))
     }
 
     fn deserialize_enum<V: Visitor<'a>>(
@@ -229,13 +249,31 @@ impl<'a, R: BufRead> DeserializerTrait<'a> for &mut Deserializer<'a, R> {
 }
 
 /// Struct responsible for deserializing a sequence of data to structs and tuples.
-struct SimpleSeqAccess<'a, 'b, R: BufRead>(&'b mut Deserializer<'a, R>);
+/// Trailing fields omitted at the end of input are reported as missing.
+/// Keeps the line of the first field, so fields after the end of that line are omitted.
+struct SimpleSeqAccess<'a, 'b, R: BufRead>(&'b mut Deserializer<'a, R>, Option<usize>);
+
+impl<'a, 'b, R: BufRead> SimpleSeqAccess<'a, 'b, R> {
+    fn new(deserializer: &'b mut Deserializer<'a, R>) -> Self {
+        Self(deserializer, None)
+    }
+}
 
 impl<'a, 'b, R: BufRead> SeqAccess<'a> for SimpleSeqAccess<'a, 'b, R> {
     type Error = Error;
 
     fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>> {
-        seed.deserialize(&mut *self.0).map(Some)
+        if self.0.end_of_input()? {
+            return Ok(None);
+        }
+
+        let deserializer = &mut *self.0;
+        // The first field is read from the next line if the current one is used up.
+        let line = *self
+            .1
+            .get_or_insert(deserializer.line + deserializer.buffer.is_empty() as usize);
+        deserializer.omitted = deserializer.line_end && deserializer.line == line;
+        seed.deserialize(deserializer).map(Some)
     }
 }
 
@@ -243,6 +281,7 @@ impl<'a, R: BufRead> SeqAccess<'a> for &mut Deserializer<'a, R> {
     type Error = Error;
 
     fn next_element_seed<T: DeserializeSeed<'a>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.omitted = false;
         if self.peek_next()?.is_empty() {
             self.next()?;
             Ok(None)
@@ -256,6 +295,7 @@ impl<'a, R: BufRead> MapAccess<'a> for &mut Deserializer<'a, R> {
     type Error = Error;
 
     fn next_key_seed<K: DeserializeSeed<'a>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        self.omitted = false;
         if self.peek_next()?.is_empty() {
             self.next()?;
             Ok(None)
@@ -265,6 +305,7 @@ impl<'a, R: BufRead> MapAccess<'a> for &mut Deserializer<'a, R> {
     }
 
     fn next_value_seed<V: DeserializeSeed<'a>>(&mut self, seed: V) -> Result<V::Value> {
+        self.omitted = false;
         seed.deserialize(&mut **self)
     }
 }
@@ -281,7 +322,7 @@ impl<'a, R: BufRead> VariantAccess<'a> for &mut Deserializer<'a, R> {
     }
 
     fn tuple_variant<V: Visitor<'a>>(self, _: usize, seed: V) -> Result<V::Value> {
-        seed.visit_seq(SimpleSeqAccess(self))
+        seed.visit_seq(SimpleSeqAccess::new(self))
     }
 
     fn struct_variant<V: Visitor<'a>>(
@@ -289,7 +330,7 @@ impl<'a, R: BufRead> VariantAccess<'a> for &mut Deserializer<'a, R> {
         _: &'static [&'static str],
         seed: V,
     ) -> Result<V::Value> {
-        seed.visit_seq(SimpleSeqAccess(self))
+        seed.visit_seq(SimpleSeqAccess::new(self))
     }
 }
 
@@ -512,6 +553,28 @@ mod tests {
         test!(Option<i32>, "- 1 -", None, Some(1), None);
     }
 
+    #[test]
+    fn deserialize_option_end_of_line() {
+        test!(
+            (i32, Option<i32>, Option<i32>),
+            "1 2\n3\n4 - 5",
+            (1, Some(2), None),
+            (3, None, None),
+            (4, None, Some(5))
+        );
+    }
+
+    #[test]
+    fn deserialize_option_next_line() {
+        test!(Option<i32>, "1", Some(1));
+        test!(
+            Vec<Option<i32>>,
+            "1\n-\n2\n\n",
+            vec![Some(1), None, Some(2)]
+        );
+        test!((Vec<i32>, Option<i32>), "1\n\n2", (vec![1], Some(2)));
+    }
+
     #[test]
     fn deserialize_option_error() {
         test!(err, Option<i32>, "a", Message(_), EndOfInput);
@@ -572,6 +635,26 @@ mod tests {
         );
     }
 
+    #[derive(Debug, Deserialize, Eq, PartialEq)]
+    struct Trailing {
+        a: i32,
+        #[serde(default)]
+        b: Vec<i32>,
+    }
+
+    #[test]
+    fn deserialize_struct_end_of_input() {
+        test!(
+            Trailing,
+            "1\n2\n3\n\n4",
+            Trailing {
+                a: 1,
+                b: vec![2, 3]
+            },
+            Trailing { a: 4, b: vec![] }
+        );
+    }
+
     #[test]
     fn deserialize_enum() {
         test!(
@@ -1,6 +1,6 @@
 use crate::Instance;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use thiserror::Error;
 
 /// Schedule info for a task. Contains the start time and processor of the task.
 #[non_exhaustive]
@@ -20,31 +20,271 @@ impl ScheduleInfo {
     }
 }
 
+/// A violated constraint of a schedule found by [`Schedule::violations`].
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Error, Hash, PartialEq, Serialize)]
+pub enum Violation {
+    /// A schedule is given for a task the instance doesn't have.
+    #[error("task {task} is out of range")]
+    TaskOutOfRange { task: usize },
+    /// A frozen task isn't scheduled exactly as given by the instance.
+    #[error("frozen task {task} was moved")]
+    FrozenMoved { task: usize },
+    /// A task is scheduled on a processor the instance doesn't have.
+    #[error("task {task} is scheduled on processor {processor} which is out of range")]
+    ProcessorOutOfRange { task: usize, processor: usize },
+    /// A task is scheduled on a machine it's not eligible for.
+    #[error("task {task} is not eligible for machine {machine}")]
+    IneligibleMachine { task: usize, machine: usize },
+    /// A task starts before its release time.
+    #[error("task {task} starts at {start_time} before its release time {release_time}")]
+    BeforeRelease {
+        task: usize,
+        start_time: u64,
+        release_time: u64,
+    },
+    /// A task finishes after its due date. Frozen tasks and minimised objectives are exempt.
+    #[error("task {task} finishes at {completion_time} after its deadline {due_date}")]
+    PastDeadline {
+        task: usize,
+        completion_time: u64,
+        due_date: u64,
+    },
+    /// A task overlaps a blocked window of its machine.
+    #[error("task {task} overlaps a blocked window of machine {machine}")]
+    BlockedWindow { task: usize, machine: usize },
+    /// Two tasks overlap on a machine including the setup between them.
+    #[error("tasks {first} and {second} overlap on machine {machine}")]
+    MachineOverlap {
+        machine: usize,
+        first: usize,
+        second: usize,
+    },
+    /// Two conflicting tasks are processed at the same time.
+    #[error("conflicting tasks {first} and {second} overlap")]
+    ConflictOverlap { first: usize, second: usize },
+    /// Tasks processed at the same time use more units of a cumulative resource than its capacity.
+    /// It's reported at every time the usage rises above the capacity.
+    #[error("cumulative resource {resource} is used above its capacity at {time}")]
+    CapacityExceeded { resource: usize, time: u64 },
+    /// A task isn't scheduled although the objective is minimised.
+    #[error("task {task} is not scheduled")]
+    Unscheduled { task: usize },
+}
+
+/// Tasks scheduled on a machine with their processing intervals ordered by the starts.
+/// Only tasks starting at most the longest processing time before an interval can overlap it,
+/// so overlapping tasks are found in logarithmic time.
+/// A sorted vector is used since tasks are mostly added and removed at the end of a machine.
+#[derive(Clone, Debug, Default)]
+struct Occupancy {
+    tasks: Vec<(u64, usize, u64)>,
+    longest: u64,
+}
+
+impl Occupancy {
+    fn position(&self, task: usize, start: u64) -> usize {
+        self.tasks
+            .partition_point(|&(other_start, other, _)| (other_start, other) < (start, task))
+    }
+
+    /// Adds the task processed in the interval.
+    fn add(&mut self, task: usize, start: u64, end: u64) {
+        let index = self.position(task, start);
+        self.tasks.insert(index, (start, task, end));
+        self.longest = self.longest.max(end - start);
+    }
+
+    /// Removes the task starting at the given time.
+    fn remove(&mut self, task: usize, start: u64) {
+        let index = self.position(task, start);
+        if self
+            .tasks
+            .get(index)
+            .is_some_and(|&(_, other, _)| other == task)
+        {
+            self.tasks.remove(index);
+        }
+    }
+
+    /// Returns the tasks overlapping the interval with their end times.
+    fn overlapping(&self, start: u64, end: u64) -> impl Iterator<Item = (usize, u64)> + '_ {
+        let earliest = start.saturating_sub(self.longest);
+        let index = self
+            .tasks
+            .partition_point(|&(other_start, _, _)| other_start < earliest);
+        self.tasks[index..]
+            .iter()
+            .take_while(move |&&(other_start, _, _)| other_start < end)
+            .filter(move |&&(_, _, other_end)| start < other_end)
+            .map(|&(_, task, end)| (task, end))
+    }
+
+    /// Returns the latest end of the tasks.
+    /// Only tasks starting at most the longest processing time before the last start can end it.
+    fn latest_end(&self) -> Option<u64> {
+        let &(last_start, _, _) = self.tasks.last()?;
+        let earliest = last_start.saturating_sub(self.longest);
+        let index = self
+            .tasks
+            .partition_point(|&(other_start, _, _)| other_start < earliest);
+        self.tasks[index..].iter().map(|&(_, _, end)| end).max()
+    }
+}
+
 /// A schedule. Contains the schedule info for every task.
+/// It's indexed by the occupancy of machines which also gives the conflict timeline of a task.
 #[derive(Clone, Debug, Serialize)]
 pub struct Schedule<'a> {
     #[serde(skip)]
     instance: &'a Instance,
     schedule: Vec<Option<ScheduleInfo>>,
+    /// Occupancy of every machine followed by tasks on processors out of range.
+    #[serde(skip)]
+    machines: Vec<Occupancy>,
 }
 
 impl<'a> Schedule<'a> {
-    /// Creates a new schedule.
+    /// Creates a new schedule containing the frozen tasks of the instance.
     pub fn new(instance: &'a Instance) -> Self {
+        let mut schedule = Self::empty(instance);
+
+        for (task, info) in instance.frozen.iter() {
+            if task < instance.tasks.len() {
+                schedule.schedule(task, info);
+            }
+        }
+
+        schedule
+    }
+
+    /// Creates a new schedule without any task, not even the frozen ones.
+    pub fn empty(instance: &'a Instance) -> Self {
         Schedule {
             instance,
             schedule: vec![None; instance.tasks.len()],
+            machines: vec![Occupancy::default(); instance.processors + 1],
         }
     }
 
+    /// Returns the scheduled instance.
+    pub fn instance(&self) -> &'a Instance {
+        self.instance
+    }
+
     /// Schedule info for a task.
     pub fn schedule(&mut self, task: usize, schedule_info: ScheduleInfo) {
+        self.remove_schedule(task);
         self.schedule[task] = Some(schedule_info);
+
+        let end_time =
+            schedule_info.start_time + self.instance.processing_time(task, schedule_info.processor);
+        self.occupancy_mut(schedule_info.processor)
+            .add(task, schedule_info.start_time, end_time);
     }
 
     /// Removes the schedule info for a task.
     pub fn remove_schedule(&mut self, task: usize) {
-        self.schedule[task] = None;
+        if let Some(schedule_info) = self.schedule[task].take() {
+            self.occupancy_mut(schedule_info.processor)
+                .remove(task, schedule_info.start_time);
+        }
+    }
+
+    fn occupancy_mut(&mut self, machine: usize) -> &mut Occupancy {
+        let index = machine.min(self.instance.processors);
+        &mut self.machines[index]
+    }
+
+    /// Returns the tasks scheduled on the machine with their start times in the order of start times.
+    pub fn machine_tasks(&self, machine: usize) -> impl Iterator<Item = (u64, usize)> + '_ {
+        self.machines[..self.instance.processors]
+            .get(machine)
+            .into_iter()
+            .flat_map(|occupancy| &occupancy.tasks)
+            .map(|&(start_time, task, _)| (start_time, task))
+    }
+
+    /// Returns the scheduled tasks conflicting with the given task which overlap the interval
+    /// with their end times. It's the conflict timeline of the task.
+    fn conflicting(
+        &self,
+        task: usize,
+        start: u64,
+        end: u64,
+    ) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.machines.iter().flat_map(move |occupancy| {
+            occupancy
+                .overlapping(start, end)
+                .filter(move |&(other, _)| self.instance.are_conflicted(task, other))
+        })
+    }
+
+    /// Returns the other scheduled tasks using the cumulative resource which overlap the interval
+    /// with their processing intervals and units.
+    fn resource_usage(
+        &self,
+        task: usize,
+        resource: usize,
+        start: u64,
+        end: u64,
+    ) -> impl Iterator<Item = (u64, u64, u64)> + '_ {
+        self.machines.iter().flat_map(move |occupancy| {
+            occupancy
+                .overlapping(start, end)
+                .filter(move |&(other, _)| other != task)
+                .filter_map(move |(other, other_end)| {
+                    let units = self.instance.demands.demand(other, resource);
+                    let other_start = self.schedule[other]?.start_time;
+                    (units > 0).then_some((other_start, other_end, units))
+                })
+        })
+    }
+
+    /// Returns the earliest later start that might not exceed the capacity of a cumulative resource
+    /// if the task processed in the interval exceeds one.
+    /// The tasks using the resource at the first exceeding time keep using it
+    /// until the earliest of them ends, so no earlier start can help.
+    /// A task exceeding the capacity alone can never start.
+    fn overload_end(&self, task: usize, start: u64, end: u64) -> Option<u64> {
+        let mut overload_end = None;
+
+        for &(resource, units) in self.instance.demands.demands(task) {
+            let capacity = self
+                .instance
+                .cumulative_resources
+                .get(resource)
+                .map_or(0, |cumulative| cumulative.capacity);
+            let usage: Vec<(u64, u64, u64)> =
+                self.resource_usage(task, resource, start, end).collect();
+
+            // The usage only rises at the start of the interval or of another task.
+            let mut times: Vec<u64> = usage
+                .iter()
+                .map(|&(other_start, _, _)| other_start.max(start))
+                .chain([start])
+                .collect();
+            times.sort_unstable();
+            times.dedup();
+
+            let exceeding_end = times.into_iter().find_map(|time| {
+                let active = || {
+                    usage.iter().filter(move |&&(other_start, other_end, _)| {
+                        other_start <= time && time < other_end
+                    })
+                };
+                let used: u64 = active().map(|&(_, _, units)| units).sum();
+                (used + units > capacity).then(|| {
+                    active()
+                        .map(|&(_, other_end, _)| other_end)
+                        .min()
+                        .unwrap_or(u64::MAX)
+                })
+            });
+
+            overload_end = overload_end.max(exceeding_end);
+        }
+
+        overload_end
     }
 
     /// Get the schedule info for a task.
@@ -52,26 +292,109 @@ impl<'a> Schedule<'a> {
         self.schedule[task].as_ref()
     }
 
-    /// Check if the given task with the given start time is in conflict with another task.
-    pub fn in_conflict(&self, task: usize, start_time: u64) -> bool {
-        self.instance.graph.conflicts(task).iter().any(|&other| {
-            if let Some(schedule_info) = self.schedule[other] {
-                let task = &self.instance.tasks[task];
-                let other = &self.instance.tasks[other];
-                start_time < schedule_info.start_time + other.processing_time
-                    && schedule_info.start_time < start_time + task.processing_time
-            } else {
-                false
+    /// Returns the completion time of a task if it's scheduled.
+    pub fn completion_time(&self, task: usize) -> Option<u64> {
+        self.schedule[task]
+            .map(|info| info.start_time + self.instance.processing_time(task, info.processor))
+    }
+
+    /// Returns the completion time of the last task or zero if no task is scheduled.
+    pub fn makespan(&self) -> u64 {
+        self.machines
+            .iter()
+            .filter_map(Occupancy::latest_end)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Check if the given task with the given start time on the given machine
+    /// is in conflict with another task or exceeds the capacity of a cumulative resource.
+    pub fn in_conflict(&self, task: usize, start_time: u64, machine: usize) -> bool {
+        let end_time = start_time + self.instance.processing_time(task, machine);
+        self.conflicting(task, start_time, end_time)
+            .next()
+            .is_some()
+            || self.overload_end(task, start_time, end_time).is_some()
+    }
+
+    /// Calculates first available time for a task on a machine that is not in conflict with other tasks.
+    /// The time is not earlier than the minimum time and the release time of the task,
+    /// the task doesn't overlap blocked windows of the machine
+    /// and doesn't exceed the capacities of cumulative resources.
+    /// It returns None if the task is not eligible for the machine, exceeds a capacity alone
+    /// or there is no available time within the latest completion time of the task.
+    pub fn calculate_non_conflict_time(
+        &self,
+        task: usize,
+        machine: usize,
+        minimum_time: u64,
+    ) -> Option<u64> {
+        if !self.instance.is_eligible(task, machine) || self.instance.exceeds_capacity(task) {
+            return None;
+        }
+
+        let processing_time = self.instance.processing_time(task, machine);
+        let mut time = minimum_time.max(self.instance.release_time(task));
+
+        loop {
+            // Every start before the end of an overlapping conflicting task overlaps it too.
+            while let Some(end) = self
+                .conflicting(task, time, time + processing_time)
+                .map(|(_, end)| end)
+                .max()
+            {
+                time = end;
             }
-        })
+            if time + processing_time > self.instance.latest_completion(task) {
+                return None;
+            }
+            if let Some(end) = self.overload_end(task, time, time + processing_time) {
+                time = end;
+                continue;
+            }
+
+            if self.instance.fits(task, machine, time) {
+                return Some(time);
+            }
+
+            // The task overlaps a blocked window or a frozen task, none of them ends earlier.
+            time = self.machine_obstacle_end(task, machine, time)?;
+        }
+    }
+
+    /// Returns the earliest end of a blocked window or a frozen task including the setup
+    /// after the given time on the machine.
+    fn machine_obstacle_end(&self, task: usize, machine: usize, time: u64) -> Option<u64> {
+        let blocked_end = self
+            .instance
+            .unavailability
+            .windows(machine)
+            .iter()
+            .map(|&(_, end)| end)
+            .filter(|&end| end > time)
+            .min();
+        let frozen_end = self
+            .instance
+            .frozen
+            .machine_tasks(machine)
+            .iter()
+            .filter_map(|&(_, other)| {
+                self.completion_time(other)
+                    .map(|end| end + self.instance.setup_time(Some(other), task))
+            })
+            .filter(|&end| end > time)
+            .min();
+
+        blocked_end.into_iter().chain(frozen_end).min()
     }
 
     /// Calculates the score of the schedule.
+    /// Only tasks finished before their due dates are counted.
     pub fn calculate_score(&self) -> u64 {
         let mut score = 0;
-        for (schedule_info, task) in self.schedule.iter().zip(&self.instance.tasks) {
-            if let Some(schedule_info) = schedule_info {
-                if schedule_info.start_time + task.processing_time <= self.instance.deadline {
+        for (id, task) in self.instance.tasks.iter().enumerate() {
+            if let Some(completion_time) = self.completion_time(id) {
+                if completion_time <= self.instance.due_date(id) {
                     score += task.weight;
                 }
             }
@@ -80,40 +403,317 @@ impl<'a> Schedule<'a> {
     }
 
     /// Checks if schedule is valid.
+    /// Tasks can't overlap on a machine including setups between them, conflicting tasks
+    /// can't overlap at all, no task can start before its release time or finish after
+    /// its due date, tasks run only on eligible machines and never in their blocked windows,
+    /// cumulative resources are never used above their capacities
+    /// and frozen tasks are kept exactly as given.
+    /// Minimised objectives allow tasks to finish after their due dates,
+    /// but every task has to be scheduled.
     pub fn verify(&self) -> bool {
-        let mut machines = vec![BTreeMap::new(); self.instance.processors];
+        self.violations().is_empty()
+    }
 
-        for (id, schedule_info) in self.schedule.iter().enumerate() {
-            if let Some(schedule_info) = schedule_info {
-                let machine = &mut machines[schedule_info.processor];
+    /// Returns all violated constraints of the schedule checked by [`Schedule::verify`].
+    /// Every overlapping pair of tasks is reported once.
+    pub fn violations(&self) -> Vec<Violation> {
+        let mut violations: Vec<Violation> = self
+            .instance
+            .frozen
+            .iter()
+            .filter(|&(task, info)| self.schedule.get(task) != Some(&Some(info)))
+            .map(|(task, _)| Violation::FrozenMoved { task })
+            .collect();
 
-                if machine.contains_key(&schedule_info.start_time) {
-                    return false;
+        for (task, schedule_info) in self.schedule.iter().enumerate() {
+            let Some(&ScheduleInfo {
+                processor,
+                start_time,
+            }) = schedule_info.as_ref()
+            else {
+                if self.instance.objective.is_minimised() {
+                    violations.push(Violation::Unscheduled { task });
                 }
+                continue;
+            };
+
+            if processor >= self.instance.processors {
+                violations.push(Violation::ProcessorOutOfRange { task, processor });
+                continue;
+            }
+            if !self.instance.is_eligible(task, processor) {
+                violations.push(Violation::IneligibleMachine {
+                    task,
+                    machine: processor,
+                });
+            }
 
-                machine.insert(schedule_info.start_time, id);
+            let release_time = self.instance.release_time(task);
+            if start_time < release_time {
+                violations.push(Violation::BeforeRelease {
+                    task,
+                    start_time,
+                    release_time,
+                });
+            }
+
+            let completion_time = self.completion_time(task).unwrap_or(start_time);
+            let due_date = self.instance.due_date(task);
+            if completion_time > due_date
+                && !self.instance.is_frozen(task)
+                && !self.instance.objective.is_minimised()
+            {
+                violations.push(Violation::PastDeadline {
+                    task,
+                    completion_time,
+                    due_date,
+                });
+            }
+
+            if !self
+                .instance
+                .unavailability
+                .is_available(processor, start_time, completion_time)
+            {
+                violations.push(Violation::BlockedWindow {
+                    task,
+                    machine: processor,
+                });
             }
         }
 
-        for machine in machines {
-            let mut last_end_time = 0;
-            for (start_time, task) in machine {
-                if start_time < last_end_time {
-                    return false;
+        for machine in 0..self.instance.processors {
+            // The previous task and the earlier tasks still running at the current one.
+            let mut previous: Option<(usize, u64)> = None;
+            let mut active: Vec<(usize, u64)> = Vec::new();
+            for (start_time, task) in self.machine_tasks(machine) {
+                let end_time = self.completion_time(task).unwrap_or(start_time);
+
+                if let Some((previous_task, previous_end)) = previous {
+                    let setup_time = self.instance.setup_time(Some(previous_task), task);
+                    if start_time < previous_end + setup_time {
+                        violations.push(Violation::MachineOverlap {
+                            machine,
+                            first: previous_task,
+                            second: task,
+                        });
+                    }
                 }
+                active.retain(|&(_, active_end)| start_time < active_end);
+                violations.extend(
+                    active
+                        .iter()
+                        .filter(|&&(active_task, _)| {
+                            previous.map(|(previous_task, _)| previous_task) != Some(active_task)
+                        })
+                        .map(|&(active_task, _)| Violation::MachineOverlap {
+                            machine,
+                            first: active_task,
+                            second: task,
+                        }),
+                );
 
-                last_end_time = start_time + self.instance.tasks[task].processing_time;
+                previous = Some((task, end_time));
+                active.push((task, end_time));
             }
         }
 
-        for (id, schedule_info) in self.schedule.iter().enumerate() {
+        for (task, schedule_info) in self.schedule.iter().enumerate() {
             if let Some(schedule_info) = schedule_info {
-                if self.in_conflict(id, schedule_info.start_time) {
-                    return false;
+                let end_time = self
+                    .completion_time(task)
+                    .unwrap_or(schedule_info.start_time);
+                violations.extend(
+                    self.conflicting(task, schedule_info.start_time, end_time)
+                        .filter(|&(other, _)| task < other)
+                        .map(|(other, _)| Violation::ConflictOverlap {
+                            first: task,
+                            second: other,
+                        }),
+                );
+            }
+        }
+
+        for (resource, cumulative) in self.instance.cumulative_resources.iter().enumerate() {
+            // Changes of the usage ordered by time with the releases before the uses.
+            let mut changes: Vec<(u64, bool, u64)> = Vec::new();
+            for (task, schedule_info) in self.schedule.iter().enumerate() {
+                let units = self.instance.demands.demand(task, resource);
+                let Some(schedule_info) = schedule_info.filter(|_| units > 0) else {
+                    continue;
+                };
+
+                let end_time = self
+                    .completion_time(task)
+                    .unwrap_or(schedule_info.start_time);
+                changes.push((schedule_info.start_time, true, units));
+                changes.push((end_time, false, units));
+            }
+            changes.sort_unstable();
+
+            let mut usage = 0;
+            for (time, is_use, units) in changes {
+                let exceeded = usage > cumulative.capacity;
+                if is_use {
+                    usage += units;
+                } else {
+                    usage -= units;
                 }
+                if !exceeded && usage > cumulative.capacity {
+                    violations.push(Violation::CapacityExceeded { resource, time });
+                }
+            }
+        }
+
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedulers::test_utils::read_test_file;
+    use crate::{list_algorithm, ConflictGraph, CumulativeResource};
+
+    #[test]
+    fn test_in_conflict() {
+        let mut instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        instance.resources = [(1, 0), (8, 0), (20, 0), (8, 1), (33, 1)]
+            .into_iter()
+            .collect();
+        let mut schedule = list_algorithm(&instance);
+        for task in (0..instance.tasks.len()).step_by(3) {
+            schedule.remove_schedule(task);
+        }
+
+        for task in 0..instance.tasks.len() {
+            for start_time in (0..instance.deadline).step_by(7) {
+                let end_time = start_time + instance.tasks[task].processing_time;
+                let expected = instance.conflicts(task).any(|other| {
+                    schedule.get_schedule(other).is_some_and(|info| {
+                        start_time < schedule.completion_time(other).unwrap_or_default()
+                            && info.start_time < end_time
+                    })
+                });
+
+                assert_eq!(schedule.in_conflict(task, start_time, 0), expected);
             }
         }
+    }
+
+    #[test]
+    fn test_machine_tasks() {
+        let instance = read_test_file("src/test/test50.txt").expect("Error reading test file");
+        let schedule = list_algorithm(&instance);
+
+        for machine in 0..instance.processors {
+            let tasks: Vec<(u64, usize)> = schedule.machine_tasks(machine).collect();
+            assert!(tasks.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(tasks.iter().all(|&(start_time, task)| {
+                schedule.get_schedule(task) == Some(&ScheduleInfo::new(start_time, machine))
+            }));
+        }
+        assert_eq!(schedule.machine_tasks(instance.processors).count(), 0);
+    }
+
+    #[test]
+    fn test_violations() {
+        let instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(100, 0));
+        schedule.schedule(5, ScheduleInfo::new(50, 1));
+        schedule.schedule(2, ScheduleInfo::new(400, 2));
+        schedule.schedule(3, ScheduleInfo::new(0, 7));
+
+        assert!(!schedule.verify());
+        assert_eq!(
+            schedule.violations(),
+            vec![
+                Violation::PastDeadline {
+                    task: 2,
+                    completion_time: 540,
+                    due_date: 480
+                },
+                Violation::ProcessorOutOfRange {
+                    task: 3,
+                    processor: 7
+                },
+                Violation::MachineOverlap {
+                    machine: 0,
+                    first: 0,
+                    second: 1
+                },
+                Violation::ConflictOverlap {
+                    first: 0,
+                    second: 1
+                },
+                Violation::ConflictOverlap {
+                    first: 0,
+                    second: 5
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_nested_overlaps() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.graph = ConflictGraph::from_iter([]);
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(7, ScheduleInfo::new(0, 0));
+        schedule.schedule(0, ScheduleInfo::new(10, 0));
+        schedule.schedule(10, ScheduleInfo::new(20, 0));
+        schedule.schedule(6, ScheduleInfo::new(100, 0));
+
+        let overlap = |first, second| Violation::MachineOverlap {
+            machine: 0,
+            first,
+            second,
+        };
+        assert_eq!(
+            schedule.violations(),
+            vec![
+                overlap(7, 0),
+                overlap(0, 10),
+                overlap(7, 10),
+                overlap(7, 6),
+                overlap(0, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capacities() {
+        let mut instance = read_test_file("src/test/test10.txt").expect("Error reading test file");
+        instance.graph = ConflictGraph::from_iter([]);
+        instance.cumulative_resources = vec![CumulativeResource::new("operators", 3)];
+        instance.demands = [(0, 0, 2), (1, 0, 1), (2, 0, 1), (3, 0, 2)]
+            .into_iter()
+            .collect();
+
+        let mut schedule = Schedule::new(&instance);
+        schedule.schedule(0, ScheduleInfo::new(0, 0));
+        schedule.schedule(1, ScheduleInfo::new(0, 1));
+        assert!(schedule.in_conflict(2, 100, 2));
+        assert!(!schedule.in_conflict(2, 120, 2));
+        assert!(!schedule.in_conflict(4, 0, 2));
+        assert_eq!(schedule.calculate_non_conflict_time(3, 2, 0), Some(150));
 
-        true
+        schedule.schedule(2, ScheduleInfo::new(100, 2));
+        schedule.schedule(3, ScheduleInfo::new(140, 1));
+        assert_eq!(
+            schedule.violations(),
+            vec![
+                Violation::CapacityExceeded {
+                    resource: 0,
+                    time: 100
+                },
+                Violation::CapacityExceeded {
+                    resource: 0,
+                    time: 140
+                },
+            ]
+        );
     }
 }
@@ -1,26 +1,64 @@
 use crate::{Instance, Schedule, ScheduleInfo, Task};
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::BTreeSet;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 /// A task with its id.
 pub type TaskWithId = (usize, Task);
 
 /// A machine is a resource that can be used to process a task.
-/// It's ordered by free time.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+/// It's ordered and compared by free time and id, the last task doesn't take part.
+#[derive(Clone, Copy, Debug)]
 pub struct Machine {
     pub id: usize,
     pub free_time: u64,
+    pub last_task: Option<usize>,
 }
 
 impl Machine {
     /// Creates a new machine with free time 0.
     pub fn new(id: usize) -> Self {
-        Self { id, free_time: 0 }
+        Self {
+            id,
+            free_time: 0,
+            last_task: None,
+        }
+    }
+
+    fn with_last_task(id: usize, free_time: u64, last_task: Option<usize>) -> Self {
+        Self {
+            id,
+            free_time,
+            last_task,
+        }
+    }
+
+    /// Returns the earliest time the given task can start on the machine
+    /// including the setup after the last task.
+    pub fn available_time(&self, instance: &Instance, task: usize) -> u64 {
+        self.free_time + instance.setup_time(self.last_task, task)
     }
 
-    fn with_free_time(id: usize, free_time: u64) -> Self {
-        Self { id, free_time }
+    /// Assigns a task to the machine which keeps it busy until the given time.
+    pub fn assign(&mut self, task: usize, free_time: u64) {
+        self.free_time = free_time;
+        self.last_task = Some(task);
+    }
+}
+
+impl PartialEq for Machine {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Machine {}
+
+impl Hash for Machine {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.free_time, self.id).hash(state);
     }
 }
 
@@ -39,39 +77,91 @@ impl Ord for Machine {
     }
 }
 
+/// Removes the machine with the earliest free time the task is eligible for from the set.
+pub fn pop_eligible_machine(
+    machines: &mut BTreeSet<Machine>,
+    instance: &Instance,
+    task: usize,
+) -> Option<Machine> {
+    let machine = machines
+        .iter()
+        .find(|machine| instance.is_eligible(task, machine.id))
+        .copied();
+    machine.filter(|machine| machines.remove(machine))
+}
+
 /// Compares two tasks by their weight and processing time.
 pub fn weighted_task_comparator(a: &TaskWithId, b: &TaskWithId) -> Ordering {
-    (a.1.processing_time * b.1.weight).cmp(&(b.1.processing_time * a.1.weight))
+    (a.1.processing_time as u128 * b.1.weight as u128)
+        .cmp(&(b.1.processing_time as u128 * a.1.weight as u128))
+}
+
+/// Changes made by a trial reorganization of a schedule builder needed to roll it back.
+#[derive(Clone, Debug, Default)]
+struct Journal {
+    schedule: Vec<(usize, Option<ScheduleInfo>)>,
+    machines: Vec<(usize, Vec<usize>)>,
+    tardy_tasks: Option<Vec<usize>>,
+    score: i64,
 }
 
 /// A builder for creating a schedule.
 /// It's used to schedule tasks on machines with utility methods.
+/// The score of the schedule for the objective of the instance is maintained incrementally.
 #[derive(Clone, Debug)]
 pub struct ScheduleBuilder<'a> {
     instance: &'a Instance,
     schedule: Schedule<'a>,
     machines: Vec<Vec<usize>>,
     tardy_tasks: Vec<usize>,
+    score: i64,
+    latest_starts: Arc<[Option<u64>]>,
+    journal: Option<Journal>,
 }
 
 impl<'a> ScheduleBuilder<'a> {
     /// Creates a new schedule builder.
     pub fn new(instance: &'a Instance) -> Self {
+        let schedule = Schedule::new(instance);
+        let score = (0..instance.tasks.len())
+            .map(|task| {
+                instance
+                    .objective
+                    .task_score(instance, task, schedule.completion_time(task))
+            })
+            .sum();
+        let latest_starts = (0..instance.tasks.len())
+            .map(|task| {
+                let processing_time = instance.min_processing_time(task)?;
+                instance
+                    .latest_completion(task)
+                    .checked_sub(processing_time)
+            })
+            .collect();
+
         Self {
             instance,
-            schedule: Schedule::new(instance),
+            schedule,
             machines: vec![Vec::new(); instance.processors],
             tardy_tasks: Vec::new(),
+            score,
+            latest_starts,
+            journal: None,
         }
     }
 
     /// Schedules a task on a machine at a given time.
     pub fn schedule(&mut self, task: usize, time: u64, machine: usize) {
-        let info = ScheduleInfo::new(time, machine);
-        self.schedule.schedule(task, info);
+        self.record_machine(machine);
+        self.set_schedule(task, Some(ScheduleInfo::new(time, machine)));
         self.machines[machine].push(task);
     }
 
+    /// Returns the instance being scheduled.
+    pub fn instance(&self) -> &'a Instance {
+        self.instance
+    }
+
     /// Returns the schedule for a task.
     pub fn get_schedule(&self, task: usize) -> Option<&ScheduleInfo> {
         self.schedule.get_schedule(task)
@@ -79,6 +169,7 @@ impl<'a> ScheduleBuilder<'a> {
 
     /// Marks a task as tardy.
     pub fn tardy(&mut self, task: usize) {
+        self.record_tardy();
         self.tardy_tasks.push(task);
     }
 
@@ -97,9 +188,29 @@ impl<'a> ScheduleBuilder<'a> {
         self.tardy_tasks.len()
     }
 
-    /// Calculates the score of the schedule.
-    pub fn calculate_score(&self) -> u64 {
-        self.schedule.calculate_score()
+    /// Returns the task at the given position of a machine.
+    pub fn machine_task(&self, machine: usize, index: usize) -> usize {
+        self.machines[machine][index]
+    }
+
+    /// Returns the tardy task at the given position.
+    pub fn tardy_task(&self, index: usize) -> usize {
+        self.tardy_tasks[index]
+    }
+
+    /// Returns the score of the schedule for the objective of the instance, see [`crate::Objective::score`].
+    pub fn objective_score(&self) -> i64 {
+        self.score - self.instance.objective.schedule_cost(&self.schedule)
+    }
+
+    /// Returns a hash of the machine sequences and the schedule identifying the solution.
+    pub fn fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.machines.hash(&mut hasher);
+        for task in 0..self.instance.tasks.len() {
+            self.schedule.get_schedule(task).hash(&mut hasher);
+        }
+        hasher.finish()
     }
 
     /// Creates an ordered set of machines with order of free time.
@@ -108,59 +219,57 @@ impl<'a> ScheduleBuilder<'a> {
             .iter()
             .enumerate()
             .map(|(id, tasks)| {
-                let free_time = tasks
-                    .last()
-                    .and_then(|&task| self.schedule.get_schedule(task).map(|info| (task, info)))
-                    .map(|(task, info)| info.start_time + self.instance.tasks[task].processing_time)
+                let last_task = tasks.last().copied();
+                let free_time = last_task
+                    .and_then(|task| self.schedule.completion_time(task))
                     .unwrap_or_default();
-                Machine::with_free_time(id, free_time)
+                Machine::with_last_task(id, free_time, last_task)
             })
             .collect()
     }
 
-    /// Check if the given task with the given start time is in conflict with another task.
-    pub fn in_conflict(&self, task: usize, start_time: u64) -> bool {
-        self.schedule.in_conflict(task, start_time)
+    /// Check if the given task with the given start time on the given machine
+    /// is in conflict with another task.
+    pub fn in_conflict(&self, task: usize, start_time: u64, machine: usize) -> bool {
+        self.schedule.in_conflict(task, start_time, machine)
     }
 
-    /// Calculates first available time for a task that is not in conflict with other tasks.
-    /// It returns None if there is no available time within deadline.
-    pub fn calculate_non_conflict_time(&self, task: usize, minimum_time: u64) -> Option<u64> {
-        let processing_time = self.instance.tasks[task].processing_time;
-        let mut times: Vec<_> = self
-            .instance
-            .graph
-            .conflicts(task)
-            .iter()
-            .filter_map(|&other| {
-                self.schedule
-                    .get_schedule(other)
-                    .map(|info| info.start_time + self.instance.tasks[other].processing_time)
-            })
-            .filter(|&time| time >= minimum_time)
-            .filter(|&time| time + processing_time <= self.instance.deadline)
-            .filter(|&time| !self.schedule.in_conflict(task, time))
-            .collect();
-        times.sort_unstable();
-        times.first().copied()
+    /// Calculates first available time for a task on a machine that is not in conflict with other tasks.
+    /// The time is not earlier than the minimum time and the release time of the task.
+    /// It returns None if the task is not eligible for the machine
+    /// or there is no available time within the due date of the task.
+    pub fn calculate_non_conflict_time(
+        &self,
+        task: usize,
+        machine: usize,
+        minimum_time: u64,
+    ) -> Option<u64> {
+        self.schedule
+            .calculate_non_conflict_time(task, machine, minimum_time)
     }
 
     /// Reorganizes the schedule using the given operations.
     /// It removes the tasks that are changed and fixes the machines and tardy tasks.
-    /// The op function should return a tuple with machine id, index, and tardy tasks.
-    pub fn reorganize_schedule<F>(&mut self, op: F)
+    /// The op function may change only the given machines and the tardy tasks.
+    /// It should return a tuple with machine id, index, and tardy tasks.
+    pub fn reorganize_schedule<F>(&mut self, changed_machines: &[usize], op: F)
     where
         F: FnOnce(&mut [Vec<usize>], &mut Vec<usize>) -> (Vec<(usize, usize)>, Vec<usize>),
     {
+        for &machine in changed_machines {
+            self.record_machine(machine);
+        }
+        self.record_tardy();
+
         let (machines, tardy) = op(&mut self.machines, &mut self.tardy_tasks);
 
         for task in tardy {
-            self.schedule.remove_schedule(task);
+            self.set_schedule(task, None);
         }
 
-        for (machine, index) in &machines {
-            for &task in &self.machines[*machine][*index..] {
-                self.schedule.remove_schedule(task);
+        for &(machine, index) in &machines {
+            for index in index..self.machines[machine].len() {
+                self.set_schedule(self.machines[machine][index], None);
             }
         }
 
@@ -171,31 +280,100 @@ impl<'a> ScheduleBuilder<'a> {
         self.fix_tardy();
     }
 
-    fn fix_machine(&mut self, machine: usize, start_index: usize) {
-        let mut free_time = if start_index == 0 {
-            0
-        } else {
-            let task = self.machines[machine][start_index - 1];
-            self.schedule
-                .get_schedule(task)
-                .map(|info| info.start_time + self.instance.tasks[task].processing_time)
-                .unwrap_or_default()
-        };
-
-        for &task in &self.machines[machine][start_index..] {
-            let processing_time = self.instance.tasks[task].processing_time;
-            let time = if self.schedule.in_conflict(task, free_time) {
-                self.calculate_non_conflict_time(task, free_time)
-            } else if free_time + processing_time <= self.instance.deadline {
-                Some(free_time)
-            } else {
-                None
-            };
+    /// Applies the change, inspects the changed builder and rolls the change back.
+    /// Only the tasks and machines touched by the change are restored,
+    /// so it's cheaper than changing a clone of the builder.
+    pub fn trial<C, I, T>(&mut self, change: C, inspect: I) -> T
+    where
+        C: FnOnce(&mut Self),
+        I: FnOnce(&Self) -> T,
+    {
+        self.journal = Some(Journal {
+            score: self.score,
+            ..Journal::default()
+        });
+
+        change(self);
+        let result = inspect(self);
+
+        let journal = self.journal.take().expect("Journal of the trial");
+        for (task, info) in journal.schedule.into_iter().rev() {
+            match info {
+                Some(info) => self.schedule.schedule(task, info),
+                None => self.schedule.remove_schedule(task),
+            }
+        }
+        for (machine, tasks) in journal.machines {
+            self.machines[machine] = tasks;
+        }
+        if let Some(tardy_tasks) = journal.tardy_tasks {
+            self.tardy_tasks = tardy_tasks;
+        }
+        self.score = journal.score;
+
+        result
+    }
+
+    /// Returns the part of the score contributed by the task.
+    fn task_score(&self, task: usize) -> i64 {
+        self.instance
+            .objective
+            .task_score(self.instance, task, self.schedule.completion_time(task))
+    }
+
+    /// Changes the schedule of the task keeping the score and the journal up to date.
+    fn set_schedule(&mut self, task: usize, info: Option<ScheduleInfo>) {
+        if let Some(journal) = &mut self.journal {
+            journal
+                .schedule
+                .push((task, self.schedule.get_schedule(task).copied()));
+        }
 
-            if let Some(time) = time {
-                let info = ScheduleInfo::new(time, machine);
-                self.schedule.schedule(task, info);
-                free_time = time + processing_time;
+        self.score -= self.task_score(task);
+        match info {
+            Some(info) => self.schedule.schedule(task, info),
+            None => self.schedule.remove_schedule(task),
+        }
+        self.score += self.task_score(task);
+    }
+
+    /// Records the tasks of the machine before its first change in a trial.
+    fn record_machine(&mut self, machine: usize) {
+        if let Some(journal) = &mut self.journal {
+            if journal.machines.iter().all(|&(other, _)| other != machine) {
+                journal
+                    .machines
+                    .push((machine, self.machines[machine].clone()));
+            }
+        }
+    }
+
+    /// Records the tardy tasks before their first change in a trial.
+    fn record_tardy(&mut self) {
+        if let Some(journal) = &mut self.journal {
+            if journal.tardy_tasks.is_none() {
+                journal.tardy_tasks = Some(self.tardy_tasks.clone());
+            }
+        }
+    }
+
+    fn fix_machine(&mut self, machine: usize, start_index: usize) {
+        self.record_machine(machine);
+
+        let mut last_task = start_index
+            .checked_sub(1)
+            .map(|index| self.machines[machine][index]);
+        let mut free_time = last_task
+            .and_then(|task| self.schedule.completion_time(task))
+            .unwrap_or_default();
+
+        for index in start_index..self.machines[machine].len() {
+            let task = self.machines[machine][index];
+            let minimum_time = free_time + self.instance.setup_time(last_task, task);
+            if let Some(time) = self.calculate_non_conflict_time(task, machine, minimum_time) {
+                self.set_schedule(task, Some(ScheduleInfo::new(time, machine)));
+                free_time = time + self.instance.processing_time(task, machine);
+                last_task = Some(task);
             } else {
                 self.tardy_tasks.push(task);
             }
@@ -205,6 +383,7 @@ impl<'a> ScheduleBuilder<'a> {
     }
 
     fn fix_tardy(&mut self) {
+        self.record_tardy();
         self.tardy_tasks.sort_unstable_by(|&a, &b| {
             weighted_task_comparator(&(a, self.instance.tasks[a]), &(b, self.instance.tasks[b]))
         });
@@ -215,21 +394,27 @@ impl<'a> ScheduleBuilder<'a> {
         std::mem::swap(&mut self.tardy_tasks, &mut tasks);
 
         for task in tasks {
-            let mut machine = machines.pop_first().expect("No available machines");
-
-            let time = if self.in_conflict(task, machine.free_time) {
-                self.calculate_non_conflict_time(task, machine.free_time)
-            } else if machine.free_time + self.instance.tasks[task].processing_time
-                <= self.instance.deadline
+            // No machine is free early enough to finish the task in time.
+            let earliest = machines.first().map(|machine| machine.free_time);
+            if earliest
+                .is_some_and(|time| self.latest_starts[task].iter().all(|&start| start < time))
             {
-                Some(machine.free_time)
-            } else {
-                None
+                self.tardy(task);
+                continue;
+            }
+
+            let Some(mut machine) = pop_eligible_machine(&mut machines, self.instance, task) else {
+                self.tardy(task);
+                continue;
             };
 
-            if let Some(time) = time {
+            if let Some(time) = self.calculate_non_conflict_time(
+                task,
+                machine.id,
+                machine.available_time(self.instance, task),
+            ) {
                 self.schedule(task, time, machine.id);
-                machine.free_time = time + self.instance.tasks[task].processing_time;
+                machine.assign(task, time + self.instance.processing_time(task, machine.id));
             } else {
                 self.tardy(task);
             }
@@ -1,33 +1,270 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
 use crate::Error;
-use scheduling_conflicts::{schedulers, Instance, Schedule, Scheduler};
-use serde::Deserialize;
+use parking_lot::Mutex;
+use scheduling_conflicts::bounds::Evaluation;
+use scheduling_conflicts::evaluation::evaluate_plan;
+use scheduling_conflicts::{
+    schedulers, Frozen, Instance, Observer, Pareto, Portfolio, Progress, Schedule, ScheduleInfo,
+    Scheduler, SimulatedAnnealing, TabuSearch, Tresoldi, Vns,
+};
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window};
+
+/// Event with the progress of a running scheduling.
+const PROGRESS_EVENT: &str = "scheduling-progress";
+
+/// Cancellation flags of scheduling runs by their ids, kept in the managed state of the app.
+/// A run is created before it starts, so it can be cancelled before the scheduling begins,
+/// and it's removed when the scheduling finishes.
+#[derive(Debug, Default)]
+pub struct SchedulingRuns {
+    next_id: AtomicU64,
+    cancelled: Mutex<HashMap<u64, Arc<AtomicBool>>>,
+}
+
+impl SchedulingRuns {
+    /// Creates a new run and returns its id.
+    fn create(&self) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.cancelled.lock().insert(id, Arc::default());
+        id
+    }
+
+    /// Returns the cancellation flag of the run, creating it if the id isn't known.
+    fn flag(&self, id: u64) -> Arc<AtomicBool> {
+        self.cancelled.lock().entry(id).or_default().clone()
+    }
+
+    /// Cancels the run if it's not finished yet.
+    fn cancel(&self, id: u64) {
+        if let Some(cancelled) = self.cancelled.lock().get(&id) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Forgets the finished run.
+    fn finish(&self, id: u64) {
+        self.cancelled.lock().remove(&id);
+    }
+}
+
+/// Progress of a scheduling run emitted to the window.
+#[derive(Clone, Copy, Debug, Serialize)]
+struct RunProgress {
+    run: u64,
+    #[serde(flatten)]
+    progress: Progress,
+}
 
 #[derive(Debug, Deserialize)]
 pub enum Algorithm {
     List,
-    VNS,
-    Tresoldi,
+    VNS(Vns),
+    Tresoldi(Tresoldi),
+    BranchAndBound,
+    Portfolio(Portfolio),
+    TabuSearch(TabuSearch),
+    SimulatedAnnealing(SimulatedAnnealing),
+}
+
+impl Algorithm {
+    /// Returns whether the algorithm falls back to the list algorithm for the instance,
+    /// e.g. Tresoldi for minimised objectives or cumulative resources.
+    fn falls_back_to_list(&self, instance: &Instance) -> bool {
+        match self {
+            Algorithm::Tresoldi(_) => Tresoldi::uses_list_algorithm(instance),
+            Algorithm::BranchAndBound => schedulers::BranchAndBound::uses_list_algorithm(instance),
+            _ => false,
+        }
+    }
 }
 
 impl Scheduler for Algorithm {
-    fn schedule(self, instance: &Instance) -> Schedule {
+    fn schedule(self, instance: &Instance) -> Schedule<'_> {
+        self.schedule_observed(instance, &())
+    }
+
+    fn schedule_observed<'a>(
+        self,
+        instance: &'a Instance,
+        observer: &dyn Observer,
+    ) -> Schedule<'a> {
         match self {
             Algorithm::List => schedulers::list_algorithm(instance),
-            Algorithm::VNS => schedulers::vns(instance),
-            Algorithm::Tresoldi => schedulers::tresoldi(instance),
+            Algorithm::VNS(vns) => vns.schedule_observed(instance, observer),
+            Algorithm::Tresoldi(tresoldi) => tresoldi.schedule_observed(instance, observer),
+            Algorithm::BranchAndBound => {
+                schedulers::BranchAndBound::default().schedule_observed(instance, observer)
+            }
+            Algorithm::Portfolio(portfolio) => portfolio.schedule_observed(instance, observer),
+            Algorithm::TabuSearch(tabu_search) => tabu_search.schedule_observed(instance, observer),
+            Algorithm::SimulatedAnnealing(annealing) => {
+                annealing.schedule_observed(instance, observer)
+            }
+        }
+    }
+}
+
+/// Emits the progress of a scheduling run to the window and stops it when it's cancelled.
+struct WindowObserver {
+    window: Window,
+    run: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl WindowObserver {
+    fn new(window: Window, runs: &SchedulingRuns, run: u64) -> Self {
+        Self {
+            window,
+            run,
+            cancelled: runs.flag(run),
         }
     }
 }
 
+impl Observer for WindowObserver {
+    fn progress(&self, progress: Progress) {
+        let progress = RunProgress {
+            run: self.run,
+            progress,
+        };
+        let _ = self.window.emit(PROGRESS_EVENT, progress);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Creates a scheduling run and returns its id.
+/// The id is passed to the scheduling command and to `cancel_scheduling`.
+#[tauri::command]
+pub fn create_scheduling_run(runs: State<'_, SchedulingRuns>) -> u64 {
+    runs.create()
+}
+
+/// A schedule with its score, upper bound, optimality gap and objective value.
+/// `list_fallback` tells whether the chosen algorithm scheduled the instance
+/// by the list algorithm instead.
+#[derive(Debug, Serialize)]
+struct ScheduleResult<'a> {
+    #[serde(flatten)]
+    schedule: Schedule<'a>,
+    #[serde(flatten)]
+    evaluation: Evaluation,
+    list_fallback: bool,
+}
+
+/// Schedules the instance with the given algorithm as the run created by `create_scheduling_run`.
+/// Frozen assignments, if given, replace the frozen tasks of the instance and are kept exactly.
+/// The progress is emitted as `scheduling-progress` events with the run id
+/// until the run finishes or is cancelled.
+/// Invalid instances are rejected before scheduling.
 #[tauri::command]
 pub async fn run_scheduling_conflicts(
+    window: Window,
+    runs: State<'_, SchedulingRuns>,
+    run: u64,
     instance: Instance,
     algorithm: Algorithm,
+    frozen: Option<Frozen>,
+) -> Result<String, Error> {
+    let observer = WindowObserver::new(window, &runs, run);
+    let result = schedule_conflicts(instance, algorithm, frozen, &observer);
+    runs.finish(run);
+    result
+}
+
+fn schedule_conflicts(
+    mut instance: Instance,
+    algorithm: Algorithm,
+    frozen: Option<Frozen>,
+    observer: &WindowObserver,
+) -> Result<String, Error> {
+    if let Some(frozen) = frozen {
+        instance.frozen = frozen;
+    }
+    instance.validate()?;
+
+    let list_fallback = algorithm.falls_back_to_list(&instance);
+    let schedule = algorithm.schedule_observed(&instance, observer);
+    let violations = schedule.violations();
+    if !violations.is_empty() {
+        Err(Error::InvalidSchedule(violations))
+    } else {
+        let evaluation = Evaluation::new(&schedule);
+        let result = ScheduleResult {
+            schedule,
+            evaluation,
+            list_fallback,
+        };
+        serde_json::to_string(&result).map_err(Error::from)
+    }
+}
+
+/// Approximates the Pareto front of the weight of tasks on time, the makespan
+/// and the number of used machines with the given parameters
+/// as the run created by `create_scheduling_run`.
+/// Frozen assignments, if given, replace the frozen tasks of the instance and are kept exactly.
+/// The progress is emitted as `scheduling-progress` events with the run id
+/// until the run finishes or is cancelled.
+/// Returns the non-dominated schedules with their criteria.
+#[tauri::command]
+pub async fn run_pareto_front(
+    window: Window,
+    runs: State<'_, SchedulingRuns>,
+    run: u64,
+    instance: Instance,
+    pareto: Pareto,
+    frozen: Option<Frozen>,
+) -> Result<String, Error> {
+    let observer = WindowObserver::new(window, &runs, run);
+    let result = pareto_front(instance, pareto, frozen, &observer);
+    runs.finish(run);
+    result
+}
+
+fn pareto_front(
+    mut instance: Instance,
+    pareto: Pareto,
+    frozen: Option<Frozen>,
+    observer: &WindowObserver,
 ) -> Result<String, Error> {
-    let schedule = algorithm.schedule(&instance);
-    if !schedule.verify() {
-        Err(Error::InvalidSchedule)
+    if let Some(frozen) = frozen {
+        instance.frozen = frozen;
+    }
+    instance.validate()?;
+
+    let front = pareto.front_observed(&instance, observer);
+    if let Some(violations) = front
+        .iter()
+        .map(|point| point.schedule.violations())
+        .find(|violations| !violations.is_empty())
+    {
+        Err(Error::InvalidSchedule(violations))
     } else {
-        serde_json::to_string(&schedule).map_err(Error::from)
+        serde_json::to_string(&front).map_err(Error::from)
     }
 }
+
+/// Evaluates a schedule of the instance given for every task, e.g. edited by hand.
+/// Returns the score with the upper bound and gap, the violated constraints
+/// and the utilisation of machines.
+#[tauri::command]
+pub fn evaluate_schedule(
+    instance: Instance,
+    schedule: Vec<Option<ScheduleInfo>>,
+) -> Result<String, Error> {
+    instance.validate()?;
+    serde_json::to_string(&evaluate_plan(&instance, &schedule)).map_err(Error::from)
+}
+
+/// Stops the scheduling run with the given id. It returns the best schedule found so far.
+/// A run cancelled before its scheduling starts stops right away.
+#[tauri::command]
+pub fn cancel_scheduling(runs: State<'_, SchedulingRuns>, run: u64) {
+    runs.cancel(run);
+}
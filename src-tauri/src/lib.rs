@@ -1,5 +1,6 @@
 use std::num::ParseIntError;
 
+use scheduling_conflicts::{InstanceError, Violation};
 use serde::Serialize;
 use serde_json::Error as SerdeError;
 use thiserror::Error;
@@ -17,12 +18,23 @@ pub enum Error {
     Serde(String),
     #[error("Error from python: {0}")]
     Python(String),
-    #[error("Algorithm failed to produce a valid schedule. This is a bug.")]
-    InvalidSchedule,
+    #[error("Algorithm failed to produce a valid schedule. This is a bug. Violations: {}", list_violations(.0))]
+    InvalidSchedule(Vec<Violation>),
+    #[error("Invalid instance: {0}")]
+    InvalidInstance(#[from] InstanceError),
     #[error("Import / export error: {0}")]
     ImportExport(String),
 }
 
+/// Joins the descriptions of the violations into a single line.
+fn list_violations(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(Violation::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
 impl Serialize for Error {
     fn serialize<S: serde::ser::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
         serializer.serialize_str(self.to_string().as_ref())
@@ -12,8 +12,13 @@ use app::python3api::Python;
 
 fn main() {
     let app = tauri::Builder::default()
+        .manage(SchedulingRuns::default())
         .invoke_handler(tauri::generate_handler![
+            create_scheduling_run,
             run_scheduling_conflicts,
+            cancel_scheduling,
+            run_pareto_front,
+            evaluate_schedule,
             run_flow,
             run_bin_packing,
             import,
@@ -1,4 +1,5 @@
 import { invoke } from '@tauri-apps/api'
+import { listen, type UnlistenFn } from '@tauri-apps/api/event'
 import type { BusinessTask, RectInfo } from '@/composables/TaskComposable'
 import { useToast } from 'bootstrap-vue-next'
 import type { Line } from '@/views/DeliveriesView.vue'
@@ -6,23 +7,128 @@ import type { Line } from '@/views/DeliveriesView.vue'
 export enum ConflictAlgorithm {
   List = 'List',
   VNS = 'VNS',
-  Tresoldi = 'Tresoldi'
+  Tresoldi = 'Tresoldi',
+  BranchAndBound = 'BranchAndBound',
+  Portfolio = 'Portfolio',
+  TabuSearch = 'TabuSearch',
+  SimulatedAnnealing = 'SimulatedAnnealing'
+}
+
+export enum VnsNeighborhood {
+  SwapSingleMachine = 'SwapSingleMachine',
+  MoveSingleMachine = 'MoveSingleMachine',
+  SwapTwoMachines = 'SwapTwoMachines',
+  MoveTwoMachines = 'MoveTwoMachines',
+  ReplaceWithTardy = 'ReplaceWithTardy',
+  AddTardy = 'AddTardy'
+}
+
+export interface VnsParams {
+  seed?: number
+  shakes?: number
+  perturbation?: number
+  time_limit?: number
+  neighborhoods?: VnsNeighborhood[]
+}
+
+export type TresoldiRestart = 'Random' | { Perturb: { swaps: number } }
+
+export interface TresoldiParams {
+  iterations?: number
+  seed?: number
+  restart?: TresoldiRestart
+  time_limit?: number
+}
+
+export type PortfolioStrategy = { Vns: VnsParams } | { Tresoldi: TresoldiParams }
+
+export interface PortfolioParams {
+  searches?: number
+  seed?: number
+  strategies?: PortfolioStrategy[]
+}
+
+export interface TabuSearchParams {
+  iterations?: number
+  tenure?: number
+  time_limit?: number
+  neighborhoods?: VnsNeighborhood[]
+}
+
+export interface SimulatedAnnealingParams {
+  seed?: number
+  initial_temperature?: number
+  cooling?: number
+  temperatures?: number
+  moves?: number
+  time_limit?: number
+  neighborhoods?: VnsNeighborhood[]
+}
+
+export interface ParetoParams {
+  limits?: number
+  vns?: VnsParams
+  time_limit?: number
+}
+
+export type AlgorithmParams =
+  | VnsParams
+  | TresoldiParams
+  | PortfolioParams
+  | TabuSearchParams
+  | SimulatedAnnealingParams
+
+export enum Objective {
+  Weight = 'Weight',
+  Makespan = 'Makespan',
+  WeightedCompletion = 'WeightedCompletion',
+  WeightedTardiness = 'WeightedTardiness'
 }
 
 export interface ConflictTask {
   processing_time: number
   weight: number
+  release_time?: number
+  due_date?: number
 }
 
 export type Conflict = [number, number]
 
 export type ConflictGraph = Conflict[]
 
+export type Eligibility = [number, number][]
+
+export type ProcessingTimes = [number, number, number][]
+
+export type SetupTimes = [number, number, number][]
+
+export type Unavailability = [number, number, number][]
+
+export type Frozen = [number, number, number][]
+
+export type Resources = [number, number][]
+
+export interface CumulativeResource {
+  name: string
+  capacity: number
+}
+
+export type Demands = [number, number, number][]
+
 export interface Instance {
   processors: number
   deadline: number
   tasks: ConflictTask[]
   graph: ConflictGraph
+  eligibility?: Eligibility
+  processing_times?: ProcessingTimes
+  setup_times?: SetupTimes
+  unavailability?: Unavailability
+  frozen?: Frozen
+  objective?: Objective
+  resources?: Resources
+  cumulative_resources?: CumulativeResource[]
+  demands?: Demands
 }
 
 export interface ScheduleInfo {
@@ -32,6 +138,42 @@ export interface ScheduleInfo {
 
 export interface Schedule {
   schedule: (ScheduleInfo | null)[]
+  score: number
+  // The bound and gap are null for minimised objectives.
+  upper_bound: number | null
+  gap: number | null
+  objective_value: number
+  // Whether the chosen algorithm scheduled the instance by the list algorithm instead.
+  list_fallback: boolean
+}
+
+export interface ParetoPoint {
+  schedule: (ScheduleInfo | null)[]
+  weight: number
+  makespan: number
+  machines: number
+}
+
+export type Violation =
+  | { TaskOutOfRange: { task: number } }
+  | { FrozenMoved: { task: number } }
+  | { ProcessorOutOfRange: { task: number; processor: number } }
+  | { IneligibleMachine: { task: number; machine: number } }
+  | { BeforeRelease: { task: number; start_time: number; release_time: number } }
+  | { PastDeadline: { task: number; completion_time: number; due_date: number } }
+  | { BlockedWindow: { task: number; machine: number } }
+  | { MachineOverlap: { machine: number; first: number; second: number } }
+  | { ConflictOverlap: { first: number; second: number } }
+  | { CapacityExceeded: { resource: number; time: number } }
+  | { Unscheduled: { task: number } }
+
+export interface ScheduleEvaluation {
+  score: number
+  upper_bound: number | null
+  gap: number | null
+  objective_value: number
+  violations: Violation[]
+  utilisation: number[]
 }
 
 export enum FlowScript {
@@ -58,16 +200,79 @@ export interface FlowSchedule {
   lacquering: FlowScheduleInfo[][]
 }
 
+function algorithmConfig(algorithm: ConflictAlgorithm, params: AlgorithmParams) {
+  switch (algorithm) {
+    case ConflictAlgorithm.VNS:
+    case ConflictAlgorithm.Tresoldi:
+    case ConflictAlgorithm.Portfolio:
+    case ConflictAlgorithm.TabuSearch:
+    case ConflictAlgorithm.SimulatedAnnealing:
+      return { [algorithm]: params }
+    default:
+      return algorithm
+  }
+}
+
+export function createSchedulingRun(): Promise<number> {
+  return invoke('create_scheduling_run').catch(onError) as Promise<number>
+}
+
 export async function scheduleConflicts(
   instance: Instance,
-  algorithm: ConflictAlgorithm
+  algorithm: ConflictAlgorithm,
+  frozen?: Frozen,
+  params: AlgorithmParams = {},
+  run?: number
 ): Promise<Schedule> {
-  const scheduleString = await invoke('run_scheduling_conflicts', { instance, algorithm }).catch(
-    onError
-  )
+  const scheduleString = await invoke('run_scheduling_conflicts', {
+    run: run ?? (await createSchedulingRun()),
+    instance,
+    algorithm: algorithmConfig(algorithm, params),
+    frozen
+  }).catch(onError)
   return await JSON.parse(scheduleString as string)
 }
 
+export async function evaluateSchedule(
+  instance: Instance,
+  schedule: (ScheduleInfo | null)[]
+): Promise<ScheduleEvaluation> {
+  const evaluationString = await invoke('evaluate_schedule', { instance, schedule }).catch(onError)
+  return await JSON.parse(evaluationString as string)
+}
+
+export async function paretoFront(
+  instance: Instance,
+  frozen?: Frozen,
+  params: ParetoParams = {},
+  run?: number
+): Promise<ParetoPoint[]> {
+  const frontString = await invoke('run_pareto_front', {
+    run: run ?? (await createSchedulingRun()),
+    instance,
+    pareto: params,
+    frozen
+  }).catch(onError)
+  return await JSON.parse(frontString as string)
+}
+
+export interface SchedulingProgress {
+  run: number
+  iteration: number
+  best_score: number
+  improved: boolean
+}
+
+export function onSchedulingProgress(
+  callback: (progress: SchedulingProgress) => void
+): Promise<UnlistenFn> {
+  return listen<SchedulingProgress>('scheduling-progress', (event) => callback(event.payload))
+}
+
+export function cancelScheduling(run: number): Promise<void> {
+  return invoke('cancel_scheduling', { run }).catch(onError) as Promise<void>
+}
+
 export function scheduleFlow(tasks: FlowTask[], script: FlowScript): Promise<FlowSchedule> {
   return invoke('run_flow', { tasks, script }).catch(onError) as Promise<FlowSchedule>
 }